@@ -1,7 +1,5 @@
 use anyhow::*;
 use aoc_common::*;
-use lazy_static::lazy_static;
-use regex::Regex;
 use std::str::FromStr;
 
 fn main() -> Result<()> {
@@ -39,21 +37,7 @@ impl FromStr for Instruction {
     type Err = Error;
 
     fn from_str(instruction: &str) -> Result<Self> {
-        lazy_static! {
-            static ref RE: Regex =
-                Regex::new(r"^(?P<op>[^\d]+) (?P<start>\d+,\d+) through (?P<end>\d+,\d+)$")
-                    .unwrap();
-        }
-
-        if let Some(caps) = RE.captures(instruction) {
-            Ok(Instruction {
-                op: caps["op"].parse()?,
-                start: caps["start"].parse()?,
-                end: caps["end"].parse()?,
-            })
-        } else {
-            bail!("line didn't match pattern")
-        }
+        scan!("{} {} through {}", instruction => Instruction { op: Op, start: Point2D, end: Point2D })
     }
 }
 
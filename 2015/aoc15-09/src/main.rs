@@ -1,6 +1,5 @@
 use anyhow::*;
 use aoc_common::*;
-use itertools::Itertools;
 use std::collections::HashMap;
 
 fn main() -> Result<()> {
@@ -8,92 +7,49 @@ fn main() -> Result<()> {
 }
 
 struct Map {
-    destinations: HashMap<String, usize>,
-    distances: Vec<Vec<u32>>,
+    // None where there's no route between two destinations
+    distances: Vec<Vec<Option<u32>>>,
 }
 
 fn parse(contents: &str) -> Result<Map> {
-    let mut id = 0;
-    let mut destinations = HashMap::new();
-    let mut distances = vec![vec![10_000; 10]; 10];
-    contents.lines().for_each(|x| {
-        if id > 10 {
-            return;
-        }
-
-        let mut parts = x.split_whitespace();
-        let from = parts.next().unwrap();
-        if !destinations.contains_key(from) {
-            destinations.insert(from.to_string(), id);
-            id += 1;
-        }
-        let from_id = destinations[from];
-        parts.next(); // skip "to"
-        let to = parts.next().unwrap();
-        if !destinations.contains_key(to) {
-            destinations.insert(to.to_string(), id);
-            id += 1;
-        }
-        let to_id = destinations[to];
-
-        parts.next(); // skip "="
-
-        distances[from_id][from_id] = 0;
-        distances[to_id][to_id] = 0;
-
-        let distance = parts.next().unwrap().parse().unwrap();
-        distances[from_id][to_id] = distance;
-        distances[to_id][from_id] = distance;
-    });
-
-    if id > 10 {
-        bail!("too many destinations");
+    let mut destinations: HashMap<String, usize> = HashMap::new();
+    let mut routes = vec![];
+    for line in contents.lines() {
+        let (route, distance) = line.split_once(" = ").ok_or_invalid()?;
+        let (from, to) = route.split_once(" to ").ok_or_invalid()?;
+        let mut id = |name: &str| {
+            let next = destinations.len();
+            *destinations.entry(name.to_string()).or_insert(next)
+        };
+        routes.push((id(from), id(to), distance.parse_wrapped()?));
     }
 
-    if distances.len() < 10 {
-        distances.truncate(destinations.len());
-        distances
-            .iter_mut()
-            .for_each(|d| d.truncate(destinations.len()));
+    let mut distances = vec![vec![None; destinations.len()]; destinations.len()];
+    for (from, to, distance) in routes {
+        distances[from][to] = Some(distance);
+        distances[to][from] = Some(distance);
     }
 
-    Ok(Map {
-        destinations,
-        distances,
-    })
+    Ok(Map { distances })
 }
 
-fn calculate_path(path: Vec<usize>, distances: &[Vec<u32>]) -> u32 {
-    path.windows(2)
-        .map(|step| {
-            let from = step[0];
-            let to = step[1];
-            distances[from][to]
-        })
-        .sum()
-}
-
-fn all_paths(map: &Map) -> Vec<u32> {
-    (0..map.destinations.len())
-        .permutations(map.destinations.len())
-        .map(|x| calculate_path(x, &map.distances))
-        .collect()
+fn best_route(map: &Map, objective: Objective) -> Option<u32> {
+    hamiltonian_tour(
+        map.distances.len(),
+        |from, to| map.distances[from][to],
+        TourShape::Path,
+        None,
+        objective,
+    )
+    .map(|tour| tour.length)
 }
 
 fn part1(map: &Map) -> Result<u32> {
-    all_paths(map)
-        .iter()
-        .min()
-        .copied()
-        .ok_or_else(|| anyhow!("no min"))
+    best_route(map, Objective::Minimize).ok_or_else(|| anyhow!("no min"))
 }
 
 fn part2(map: &Map) -> Result<u32> {
-    all_paths(map)
-        .iter()
-        .max()
-        .copied()
-        .ok_or_else(|| anyhow!("no max"))
+    best_route(map, Objective::Maximize).ok_or_else(|| anyhow!("no max"))
 }
 
 #[cfg(test)]
@@ -122,6 +78,23 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn more_than_ten_destinations() -> Result<()> {
+        // 12 towns along a road, 1 apart, with a long way round between every other pair
+        let mut routes = String::new();
+        for a in 0..12 {
+            for b in (a + 1)..12 {
+                let distance = if b == a + 1 { 1 } else { 100 };
+                routes.push_str(&format!("T{a} to T{b} = {distance}\n"));
+            }
+        }
+        let parsed = parse(&routes)?;
+
+        assert_eq!(11, part1(&parsed)?);
+
+        Ok(())
+    }
+
     const SAMPLE: &str = "\
 London to Dublin = 464
 London to Belfast = 518
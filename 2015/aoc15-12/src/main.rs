@@ -1,38 +1,67 @@
 use anyhow::*;
 use aoc_common::*;
-use json::{parse, JsonValue};
-use regex::Regex;
 
 fn main() -> Result<()> {
     run_raw(part1, part2)
 }
 
-fn part1(contents: &str) -> Result<i32> {
-    Regex::new(r"-?\d+")?
-        .find_iter(contents)
-        .map(|x| wrap_parse_error(x.as_str().parse::<i32>()))
-        .sum()
+enum Json {
+    Number(i32),
+    Text(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+fn text(input: &str) -> Parsed<'_, String> {
+    delimited(literal("\""), take_while(|c| c != '"'), literal("\""))
+        .map(str::to_string)
+        .parse_next(input)
+}
+
+fn json(input: &str) -> Parsed<'_, Json> {
+    let member = text.then(preceded(literal(":"), json));
+    number()
+        .map(Json::Number)
+        .or(text.map(Json::Text))
+        .or(delimited(literal("["), separated(json, literal(",")), literal("]")).map(Json::Array))
+        .or(
+            delimited(literal("{"), separated(member, literal(",")), literal("}"))
+                .map(Json::Object),
+        )
+        .parse_next(input)
 }
 
-fn sum_subtree(current: &JsonValue) -> i32 {
+fn sum_subtree(current: &Json, ignore_red: bool) -> i32 {
     match current {
-        JsonValue::Object(_) => {
-            if current.entries().any(|(_, value)| value == "red") {
+        Json::Object(members) => {
+            if ignore_red
+                && members
+                    .iter()
+                    .any(|(_, value)| matches!(value, Json::Text(text) if text == "red"))
+            {
                 0
             } else {
-                current.entries().map(|(_, value)| sum_subtree(value)).sum()
+                members
+                    .iter()
+                    .map(|(_, value)| sum_subtree(value, ignore_red))
+                    .sum()
             }
         }
-        JsonValue::Array(_) => current.members().map(sum_subtree).sum(),
-        JsonValue::Number(_) => current.as_i32().unwrap(),
-
-        _ => 0,
+        Json::Array(members) => members
+            .iter()
+            .map(|value| sum_subtree(value, ignore_red))
+            .sum(),
+        Json::Number(number) => *number,
+        Json::Text(_) => 0,
     }
 }
 
+fn part1(contents: &str) -> Result<i32> {
+    Ok(sum_subtree(&parse_with(contents, json)?, false))
+}
+
 fn part2(contents: &str) -> Result<i32> {
-    let json = parse(contents)?;
-    Ok(sum_subtree(&json))
+    Ok(sum_subtree(&parse_with(contents, json)?, true))
 }
 
 #[cfg(test)]
@@ -1,6 +1,5 @@
 use anyhow::*;
 use aoc_common::*;
-use itertools::Itertools;
 use std::collections::HashMap;
 use std::str::FromStr;
 
@@ -77,40 +76,26 @@ impl SeatingHappiness {
     }
 }
 
-fn all_happiness(contents: &SeatingHappiness) -> Vec<i32> {
-    (0..contents.happiness.len())
-        .permutations(contents.happiness.len())
-        .map(|x| calculate_happiness(x, &contents.happiness))
-        .collect()
-}
-
-fn calculate_happiness(seating: Vec<usize>, happiness: &[Vec<i32>]) -> i32 {
-    let head = seating[0];
-    let foot = seating[seating.len() - 1]; // technically the person to the left of head, but the naming was easier this way
-    seating
-        .windows(2)
-        .map(|neighbors| {
-            let a = neighbors[0];
-            let b = neighbors[1];
-            happiness[a][b] + happiness[b][a]
+// seating is a cycle, and each neighbor pair contributes happiness in both directions
+fn best_happiness(contents: &SeatingHappiness) -> Option<i32> {
+    let happiness = &contents.happiness;
+    let pair_happiness: Vec<Vec<i32>> = (0..happiness.len())
+        .map(|a| {
+            (0..happiness.len())
+                .map(|b| happiness[a][b] + happiness[b][a])
+                .collect()
         })
-        .sum::<i32>()
-        + happiness[head][foot]
-        + happiness[foot][head]
+        .collect();
+
+    longest_hamiltonian_cycle(&pair_happiness).map(|tour| tour.length)
 }
 
 fn part1(contents: &SeatingHappiness) -> Result<i32> {
-    all_happiness(contents)
-        .into_iter()
-        .max()
-        .ok_or_else(|| anyhow!("no happiness"))
+    best_happiness(contents).ok_or_else(|| anyhow!("no happiness"))
 }
 
 fn part2(contents: &SeatingHappiness) -> Result<i32> {
-    all_happiness(&contents.add_me())
-        .into_iter()
-        .max()
-        .ok_or_else(|| anyhow!("no happiness"))
+    best_happiness(&contents.add_me()).ok_or_else(|| anyhow!("no happiness"))
 }
 
 #[cfg(test)]
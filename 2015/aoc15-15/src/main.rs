@@ -68,38 +68,16 @@ where
     }
 }
 
-fn two_ingredient_permutations() -> Vec<Vec<i32>> {
-    let mut result = vec![];
-    for x in 1..=99 {
-        result.push(vec![x, 100 - x]);
-    }
-    result
-}
-
-// returns all the permutations for a given set of ingredients
 // ingredients must add to 100 tsps and each ingredient must have at least 1 tsp
-fn four_ingredient_permutations() -> Vec<Vec<i32>> {
-    let mut result = vec![];
-    for x in 1..=97 {
-        for y in 1..=(98 - x) {
-            for z in 1..=(99 - x - y) {
-                result.push(vec![x, y, z, 100 - x - y - z]);
-            }
-        }
-    }
-    result
-}
-
 fn get_best_cookie(ingredients: &[Ingredient], set_calories: bool) -> i32 {
-    let permutations = match ingredients.len() {
-        2 => two_ingredient_permutations(),
-        4 => four_ingredient_permutations(),
-        _ => panic!(),
-    };
+    let mut amounts = Compositions::strong(100, ingredients.len());
     let mut max_score = 0;
-    for p in permutations {
+    while let Some(tsps) = amounts.advance() {
         max_score = max(
-            total_score(p.iter().copied().zip(ingredients), set_calories),
+            total_score(
+                tsps.iter().map(|&tsp| tsp as i32).zip(ingredients),
+                set_calories,
+            ),
             max_score,
         );
     }
@@ -1,4 +1,4 @@
-use std::{collections::VecDeque, str::FromStr};
+use std::str::FromStr;
 
 use anyhow::*;
 use aoc_common::*;
@@ -44,30 +44,9 @@ impl Solution for Problem {
 
 impl Problem {
     fn determine_combinations(&mut self, target_volume: usize) {
-        let mut todo = VecDeque::new();
-
-        for (ix, c) in self.containers.iter().enumerate() {
-            let mut used = vec![false; self.containers.len()];
-            used[ix] = true;
-            todo.push_back((ix + 1, *c, used));
-        }
-
-        while let Some((first_ix, volume, used)) = todo.pop_front() {
-            if volume > target_volume {
-                continue;
-            }
-            if volume == target_volume {
-                self.combination_lengths
-                    .push(used.into_iter().filter(|&used| used).count());
-                continue;
-            }
-            for (ix, c) in self.containers.iter().enumerate().skip(first_ix) {
-                if !used[ix] {
-                    let mut used = used.clone();
-                    used[ix] = true;
-                    todo.push_back((ix + 1, volume + c, used));
-                }
-            }
+        let mut combinations = SubsetsWithSum::new(&self.containers, target_volume);
+        while let Some(used) = combinations.advance() {
+            self.combination_lengths.push(used.len());
         }
     }
 }
@@ -1,4 +1,4 @@
-use std::str::FromStr;
+use std::{cmp::Reverse, str::FromStr};
 
 use anyhow::*;
 use aoc_common::*;
@@ -192,7 +192,6 @@ impl State {
                 // player turn
                 let mut effects = process_effects(effects.to_owned(), &mut player, &mut boss);
                 if boss.hp == 0 {
-                    println!("WIN: {mana_spent}");
                     return PlayerWin {
                         mana_spent: *mana_spent,
                     };
@@ -217,7 +216,6 @@ impl State {
                 }
 
                 if boss.hp == 0 {
-                    println!("WIN: {mana_spent}");
                     return PlayerWin { mana_spent };
                 }
 
@@ -232,7 +230,6 @@ impl State {
 
                 let effects = process_effects(effects, &mut player, &mut boss);
                 if boss.hp == 0 {
-                    println!("WIN: {mana_spent}");
                     return PlayerWin { mana_spent };
                 }
                 let mut damage = boss.damage.saturating_sub(player.armor);
@@ -245,7 +242,6 @@ impl State {
                 */
                 player.hp = player.hp.saturating_sub(damage);
                 if player.hp == 0 {
-                    println!("LOSS: {mana_spent}");
                     return BossWin { mana_spent };
                 }
                 return Continue {
@@ -260,23 +256,79 @@ impl State {
     }
 }
 
+impl State {
+    // effects can be started again on the turn they wear off, and recharge pays out before the
+    // spell is cast
+    fn can_cast(&self, spell: &Spell) -> bool {
+        match self {
+            Continue {
+                player, effects, ..
+            } => {
+                let recharge = effects.iter().any(|&Effect(active, _)| active == Recharge);
+                let mana = player.mana + if recharge { 101 } else { 0 };
+                mana >= spell.cost()
+                    && !effects
+                        .iter()
+                        .any(|&Effect(active, timer)| active == *spell && timer > 1)
+            }
+            _ => false,
+        }
+    }
+}
+
 impl OptimizationState for State {
-    type Score = usize;
+    type Score = Reverse<usize>;
     type CacheKey = State;
 
     fn score(&self) -> Self::Score {
         match self {
-            &PlayerWin { mana_spent } => mana_spent,
-            &BossWin { mana_spent } => mana_spent,
-            &Continue { mana_spent, .. } => mana_spent,
+            &PlayerWin { mana_spent } => Reverse(mana_spent),
+            &BossWin { mana_spent } => Reverse(mana_spent),
+            &Continue { mana_spent, .. } => Reverse(mana_spent),
         }
     }
 
+    // everything but the mana spent so far, so reaching the same fight for more mana is pruned
     fn cache_key(&self) -> Self::CacheKey {
-        self.clone()
+        match self {
+            Continue {
+                player,
+                boss,
+                effects,
+                ..
+            } => Continue {
+                mana_spent: 0,
+                player: player.clone(),
+                boss: boss.clone(),
+                effects: effects.clone(),
+            },
+            PlayerWin { .. } => PlayerWin { mana_spent: 0 },
+            BossWin { .. } => BossWin { mana_spent: 0 },
+        }
     }
 }
 
+fn least_mana_to_win(player: Character, boss: Character) -> Option<usize> {
+    let (best_win, _) = branch_and_bound(
+        Continue {
+            mana_spent: 0,
+            player,
+            boss,
+            effects: vec![],
+        },
+        |state| {
+            SPELLS
+                .iter()
+                .filter(|spell| state.can_cast(spell))
+                .map(|spell| state.turn(spell))
+                .collect::<Vec<_>>()
+        },
+        |_| 0,
+        |state| matches!(state, PlayerWin { .. }),
+    );
+    best_win.map(|state| state.score().0)
+}
+
 struct Problem {
     boss: Character,
 }
@@ -296,38 +348,13 @@ impl Solution for Problem {
     type Part2 = usize;
 
     fn part1(&mut self) -> Result<Self::Part1> {
-        let best_win = dijkstra(
-            Continue {
-                mana_spent: 0,
-                player: Character {
-                    hp: 50,
-                    mana: 500,
-                    damage: 0,
-                    armor: 0,
-                },
-                boss: self.boss.clone(),
-                effects: vec![],
-            },
-            |state| match state {
-                Continue { player, .. } => SPELLS
-                    .iter()
-                    .filter(|spell| {
-                        let cost = spell.cost();
-                        player.mana >= cost
-                    })
-                    .map(|spell| state.turn(spell))
-                    .collect::<Vec<_>>(),
-                _ => vec![],
-            },
-            |state| match state {
-                PlayerWin { .. } => true,
-                _ => false,
-            },
-        );
-
-        best_win
-            .map(|state| state.score())
-            .ok_or_else(|| anyhow!("no solution"))
+        let player = Character {
+            hp: 50,
+            mana: 500,
+            damage: 0,
+            armor: 0,
+        };
+        least_mana_to_win(player, self.boss.clone()).ok_or_else(|| anyhow!("no solution"))
     }
 
     fn part2(&self) -> Result<Self::Part2> {
@@ -416,4 +443,23 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn cheapest_wins() {
+        let player = Character {
+            hp: 10,
+            mana: 250,
+            damage: 0,
+            armor: 0,
+        };
+        let boss = |hp| Character {
+            hp,
+            mana: 0,
+            damage: 8,
+            armor: 0,
+        };
+
+        assert_eq!(Some(226), least_mana_to_win(player.clone(), boss(13)));
+        assert_eq!(Some(641), least_mana_to_win(player, boss(14)));
+    }
 }
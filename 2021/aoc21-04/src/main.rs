@@ -1,6 +1,7 @@
 use anyhow::*;
-use aoc_common::run;
+use aoc_common::*;
 use std::fmt;
+use std::str::FromStr;
 
 fn main() -> Result<()> {
     run(parse, part1, part2)
@@ -156,46 +157,40 @@ fn bool_star(a: bool) -> &'static str {
     " "
 }
 
-fn lines_to_board(lines: &str) -> Result<Board> {
-    // remove empty lines
-    let lines: Vec<&str> = lines.lines().filter(|x| !x.is_empty()).collect();
+impl FromStr for Board {
+    type Err = Error;
 
-    if lines.len() < 5 {
-        bail!("not enough lines to construct board");
-    }
-
-    let mut board = empty_board();
-    for (x, line) in lines[..5].iter().enumerate() {
-        let cells = line
-            .split_whitespace()
-            .map(|x| Ok(x.parse().context("cell should be int")?))
-            .collect::<Result<Vec<u32>>>()?;
+    fn from_str(lines: &str) -> Result<Board> {
+        // remove empty lines
+        let lines: Vec<&str> = lines.lines().filter(|x| !x.is_empty()).collect();
 
-        if cells.len() != 5 {
-            bail!("not enough cells on line {} to construct board", x);
+        if lines.len() < 5 {
+            bail!("not enough lines to construct board");
         }
-        for (y, cell) in cells.into_iter().enumerate() {
-            board.cells[x][y] = cell;
+
+        let mut board = empty_board();
+        for (x, line) in lines[..5].iter().enumerate() {
+            let cells = line
+                .split_whitespace()
+                .map(|x| Ok(x.parse().context("cell should be int")?))
+                .collect::<Result<Vec<u32>>>()?;
+
+            if cells.len() != 5 {
+                bail!("not enough cells on line {} to construct board", x);
+            }
+            for (y, cell) in cells.into_iter().enumerate() {
+                board.cells[x][y] = cell;
+            }
         }
+        Ok(board)
     }
-    Ok(board)
 }
 
 fn parse(contents: &str) -> Result<Game> {
-    let mut parts: Vec<&str> = contents.split("\n\n").collect();
-
-    let boards = parts.split_off(1);
-
-    let draws = parts[0]
-        .split(',')
-        .filter(|x| !x.is_empty())
-        .map(|x| Ok(x.parse().context("draw should be int")?))
-        .collect::<Result<Vec<u32>>>()?;
+    let mut sections = Sections::new(contents);
 
-    let boards = boards
-        .into_iter()
-        .map(lines_to_board)
-        .collect::<Result<Vec<Board>>>()?;
+    let draws = sections.take_with(|draws| draws.parse_split(','))?;
+    let boards = sections.take_rest()?;
 
     Ok(Game { draws, boards })
 }
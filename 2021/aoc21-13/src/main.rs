@@ -1,4 +1,5 @@
 use anyhow::*;
+use aoc_common::ocr;
 use aoc_common::run;
 use aoc_common::Point2D;
 use std::collections::HashSet;
@@ -146,14 +147,18 @@ fn part1((marks, folds): &(Vec<Point2D>, Vec<Fold>)) -> Result<usize> {
     Ok(sheet.marks.len())
 }
 
-fn part2((marks, folds): &(Vec<Point2D>, Vec<Fold>)) -> Result<OrigamiSheet> {
+fn fold_all((marks, folds): &(Vec<Point2D>, Vec<Fold>)) -> OrigamiSheet {
     let mut sheet = OrigamiSheet::new(marks);
 
     for fold in folds {
         sheet.fold(*fold);
     }
 
-    Ok(sheet)
+    sheet
+}
+
+fn part2(input: &(Vec<Point2D>, Vec<Fold>)) -> Result<String> {
+    ocr(&fold_all(input).marks)
 }
 
 #[cfg(test)]
@@ -175,7 +180,8 @@ mod tests {
     fn sample_part2() -> Result<()> {
         let parsed = parse(SAMPLE)?;
 
-        let result = part2(&parsed)?;
+        // the sample folds into a square instead of letters, so check the sheet part 2 reads
+        let result = fold_all(&parsed);
 
         assert_eq!(
             result.to_string(),
@@ -187,6 +193,7 @@ mod tests {
 ##########
 "
         );
+        assert!(part2(&parsed).is_err());
 
         Ok(())
     }
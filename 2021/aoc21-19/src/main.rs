@@ -1,228 +1,55 @@
 use anyhow::*;
 use aoc_common::*;
-use nalgebra::{vector, Matrix3, Vector3};
-use std::fmt;
 use std::str::FromStr;
 
 fn main() -> Result<()> {
     run_progressive_vec(parse, part1, part2)
 }
 
-static ROTATION_MATRICES: [Matrix3<i32>; 24] = [
-    Matrix3::new(1, 0, 0, 0, 1, 0, 0, 0, 1),
-    Matrix3::new(1, 0, 0, 0, 0, 1, 0, -1, 0),
-    Matrix3::new(1, 0, 0, 0, -1, 0, 0, 0, -1),
-    Matrix3::new(1, 0, 0, 0, 0, -1, 0, 1, 0),
-    Matrix3::new(0, 1, 0, 0, 0, 1, 1, 0, 0),
-    Matrix3::new(0, 1, 0, 1, 0, 0, 0, 0, -1),
-    Matrix3::new(0, 1, 0, 0, 0, -1, -1, 0, 0),
-    Matrix3::new(0, 1, 0, -1, 0, 0, 0, 0, 1),
-    Matrix3::new(0, 0, 1, 1, 0, 0, 0, 1, 0),
-    Matrix3::new(0, 0, 1, 0, 1, 0, -1, 0, 0),
-    Matrix3::new(0, 0, 1, -1, 0, 0, 0, -1, 0),
-    Matrix3::new(0, 0, 1, 0, -1, 0, 1, 0, 0),
-    Matrix3::new(-1, 0, 0, 0, -1, 0, 0, 0, 1),
-    Matrix3::new(-1, 0, 0, 0, 0, 1, 0, 1, 0),
-    Matrix3::new(-1, 0, 0, 0, 1, 0, 0, 0, -1),
-    Matrix3::new(-1, 0, 0, 0, 0, -1, 0, -1, 0),
-    Matrix3::new(0, -1, 0, 0, 0, -1, 1, 0, 0),
-    Matrix3::new(0, -1, 0, 1, 0, 0, 0, 0, 1),
-    Matrix3::new(0, -1, 0, 0, 0, 1, -1, 0, 0),
-    Matrix3::new(0, -1, 0, -1, 0, 0, 0, 0, -1),
-    Matrix3::new(0, 0, -1, -1, 0, 0, 0, 1, 0),
-    Matrix3::new(0, 0, -1, 0, 1, 0, 1, 0, 0),
-    Matrix3::new(0, 0, -1, 1, 0, 0, 0, -1, 0),
-    Matrix3::new(0, 0, -1, 0, -1, 0, -1, 0, 0),
-];
-
-#[derive(Copy, Clone, PartialEq, Eq)]
-struct Point3D {
-    x: i32,
-    y: i32,
-    z: i32,
-}
-
-impl FromStr for Point3D {
-    type Err = Error;
-
-    fn from_str(point: &str) -> Result<Self> {
-        let parsed = point
-            .split(',')
-            .map(|x| Ok(x.parse()?))
-            .collect::<Result<Vec<i32>>>()?;
-
-        Ok(Point3D {
-            x: parsed[0],
-            y: parsed[1],
-            z: parsed[2],
-        })
-    }
-}
-
-impl Point3D {
-    fn relative_to(&self, other: Point3D) -> Point3D {
-        Point3D {
-            x: self.x - other.x,
-            y: self.y - other.y,
-            z: self.z - other.z,
-        }
-    }
-
-    fn equal_distance_from_origin(&self, other: Point3D) -> bool {
-        let mut self_abs = vec![self.x.abs(), self.y.abs(), self.z.abs()];
-        let mut other_abs = vec![other.x.abs(), other.y.abs(), other.z.abs()];
-        self_abs.sort_unstable();
-        other_abs.sort_unstable();
-
-        self_abs == other_abs
-    }
-
-    fn as_vector(&self) -> Vector3<i32> {
-        vector![self.x, self.y, self.z]
-    }
-
-    fn from_vector(v: &Vector3<i32>) -> Point3D {
-        Point3D {
-            x: v[0],
-            y: v[1],
-            z: v[2],
-        }
-    }
-}
-
-impl fmt::Display for Point3D {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{},{},{}", self.x, self.y, self.z)
-    }
-}
-
-impl fmt::Debug for Point3D {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self)
-    }
-}
+// scanners overlap when they can both see at least this many beacons
+const MIN_OVERLAP: usize = 12;
 
 #[derive(Clone, Debug)]
 struct Scanner {
-    beacons: Vec<Point3D>,
-    dist_matrix: Vec<Vec<Point3D>>,
-    other_scanners: Vec<Point3D>,
-}
-
-struct OverlappingBeacons {
-    ref_beacon: usize,
-    other_ref_beacon: usize,
-    overlapping_beacon_indices: Vec<(usize, usize)>,
+    beacons: Vec<IPoint3D>,
+    other_scanners: Vec<IPoint3D>,
 }
 
 impl Scanner {
-    fn new(beacons: Vec<Point3D>) -> Scanner {
-        let mut result = Scanner {
+    fn new(beacons: Vec<IPoint3D>) -> Scanner {
+        Scanner {
             beacons,
-            dist_matrix: vec![],
             other_scanners: vec![],
-        };
-
-        result.calc_dist_matrix();
-
-        result
-    }
-
-    fn calc_dist_matrix(&mut self) {
-        self.dist_matrix =
-            vec![vec![Point3D { x: 0, y: 0, z: 0 }; self.beacons.len()]; self.beacons.len()];
-
-        for i in 0..self.beacons.len() {
-            for j in 0..self.beacons.len() {
-                if i == j {
-                    continue;
-                }
-
-                self.dist_matrix[i][j] = self.beacons[i].relative_to(self.beacons[j]);
-            }
-        }
-    }
-
-    fn overlapping_beacons(&self, other: &Scanner) -> Option<OverlappingBeacons> {
-        for i in 0..self.beacons.len() {
-            let distances = &self.dist_matrix[i];
-            // see if we have 12 overlapping distances
-            for j in 0..other.beacons.len() {
-                let other_distances = &other.dist_matrix[j];
-
-                let overlapping: Vec<(usize, usize)> = (0..(self.beacons.len()))
-                    .filter_map(|my_beacon_ix| {
-                        let maybe_other_ix = (0..(other.beacons.len())).find(|other_beacon_ix| {
-                            distances[my_beacon_ix]
-                                .equal_distance_from_origin(other_distances[*other_beacon_ix])
-                        });
-
-                        if let Some(other_beacon_ix) = maybe_other_ix {
-                            return Some((my_beacon_ix, other_beacon_ix));
-                        }
-                        None
-                    })
-                    .collect();
-
-                if overlapping.len() >= 12 {
-                    return Some(OverlappingBeacons {
-                        ref_beacon: i,
-                        other_ref_beacon: j,
-                        overlapping_beacon_indices: overlapping,
-                    });
-                }
-            }
         }
-        None
     }
 
-    fn find_rotation(&self, other: &Scanner, overlap: &OverlappingBeacons) -> Option<Matrix3<i32>> {
-        let my_distances = &self.dist_matrix[overlap.ref_beacon];
-        let other_distances = &other.dist_matrix[overlap.other_ref_beacon];
-        ROTATION_MATRICES.into_iter().find(|&rot| {
-            overlap
-                .overlapping_beacon_indices
-                .iter()
-                .all(|(my_beacon_ix, other_beacon_ix)| {
-                    let rotated = rot * other_distances[*other_beacon_ix].as_vector();
-                    my_distances[*my_beacon_ix].as_vector() == rotated
-                })
-        })
+    fn alignment(&self, other: &Scanner) -> Option<Alignment3D> {
+        align_points(&self.beacons, &other.beacons, MIN_OVERLAP)
     }
 
     fn plot_beacons(&mut self, other: &Scanner) -> bool {
-        if let Some(overlap) = self.overlapping_beacons(other) {
-            if let Some(rot) = self.find_rotation(other, &overlap) {
-                let rotated_other_beacon =
-                    rot * other.beacons[overlap.other_ref_beacon].as_vector();
-
-                let translation =
-                    self.beacons[overlap.ref_beacon].as_vector() - rotated_other_beacon;
-                self.other_scanners.push(Point3D::from_vector(&translation));
-
-                for beacon in &other.beacons {
-                    let normalized =
-                        Point3D::from_vector(&(rot * beacon.as_vector() + translation));
-                    if !self.beacons.contains(&normalized) {
-                        self.beacons.push(normalized);
-                    }
+        if let Some(alignment) = self.alignment(other) {
+            // the other scanner sits at its own origin
+            self.other_scanners.push(alignment.translation);
+
+            for beacon in &other.beacons {
+                let normalized = alignment.apply(*beacon);
+                if !self.beacons.contains(&normalized) {
+                    self.beacons.push(normalized);
                 }
-                self.calc_dist_matrix();
-
-                return true;
             }
+
+            return true;
         }
         false
     }
 
-    fn max_manhattan_distance(&self) -> i32 {
+    fn max_manhattan_distance(&self) -> i64 {
         let mut max = 0;
 
-        for i in 0..self.other_scanners.len() {
-            for j in 0..self.other_scanners.len() {
-                let a = self.other_scanners[i];
-                let b = self.other_scanners[j];
-                let dist = (a.x - b.x).abs() + (a.y - b.y).abs() + (a.z - b.z).abs();
+        for a in &self.other_scanners {
+            for b in &self.other_scanners {
+                let dist = a.manhattan_distance(*b);
                 if dist > max {
                     max = dist
                 }
@@ -242,7 +69,7 @@ impl FromStr for Scanner {
                 .lines()
                 .skip(1)
                 .map(|x| x.parse())
-                .collect::<Result<Vec<Point3D>>>()?,
+                .collect::<Result<Vec<IPoint3D>>>()?,
         ))
     }
 }
@@ -267,7 +94,7 @@ fn part1(scanners: &[Scanner]) -> Result<(usize, Scanner)> {
     Ok((reference_scanner.beacons.len(), reference_scanner))
 }
 
-fn part2(_: &[Scanner], scanner: &Scanner) -> Result<i32> {
+fn part2(_: &[Scanner], scanner: &Scanner) -> Result<i64> {
     Ok(scanner.max_manhattan_distance())
 }
 
@@ -279,11 +106,17 @@ mod tests {
     fn overlapping_beacons() -> Result<()> {
         let scanners = parse(SAMPLE)?;
 
-        let result = scanners[0]
-            .overlapping_beacons(&scanners[1])
+        let alignment = scanners[0]
+            .alignment(&scanners[1])
             .ok_or_else(|| anyhow!("overlapping_beacons failed"))?;
 
-        assert_eq!(result.overlapping_beacon_indices.len(), 12);
+        let overlapping = scanners[1]
+            .beacons
+            .iter()
+            .filter(|beacon| scanners[0].beacons.contains(&alignment.apply(**beacon)))
+            .count();
+        assert_eq!(overlapping, 12);
+        assert_eq!(alignment.translation, ipt3(68, -1246, -43));
 
         Ok(())
     }
@@ -1,284 +1,34 @@
 use anyhow::*;
 use aoc_common::*;
-use std::fmt;
 use std::str::FromStr;
 
 fn main() -> Result<()> {
     run_vec(parse, part1, part2)
 }
 
-#[derive(Clone, Debug)]
-struct ReactorCore {
-    on: Vec<Cuboid>,
-}
-
-impl ReactorCore {
-    fn new() -> ReactorCore {
-        ReactorCore { on: Vec::new() }
-    }
-
-    fn process(&mut self, instruction: &Instruction) {
-        match instruction {
-            On(cuboid) => self.turn_on(cuboid),
-            Off(cuboid) => self.turn_off(cuboid),
-        }
-    }
-
-    // a general purpose solution of this should probably combine adjacent cubes that are the same
-    // size in 2 dimensions
-    fn turn_on(&mut self, cuboid: &Cuboid) {
-        // turn this cuboid off first, then we can simply add this cuboid to the on list
-        self.turn_off(cuboid);
-        self.on.push(*cuboid);
-    }
-
-    fn turn_off(&mut self, cuboid: &Cuboid) {
-        'outer: loop {
-            for i in 0..self.on.len() {
-                let already_on = self.on[i];
-                if cuboid.overlaps(&already_on) {
-                    self.on.splice(i..=i, cuboid.difference(&already_on));
-                    // need to start over since we changed "on"
-                    continue 'outer;
-                }
-            }
-            break;
-        }
-    }
-
-    fn volume_on(&self) -> u64 {
-        self.on.iter().map(|x| x.volume()).sum()
-    }
-}
-
-#[derive(Copy, Clone, PartialEq, Eq, Hash)]
-struct Point3D {
-    x: i32,
-    y: i32,
-    z: i32,
-}
-
-impl FromStr for Point3D {
-    type Err = Error;
+// the region the reactor is initialized in
+const INITIALIZATION_REGION: ICuboid = ICuboid {
+    origin: IPoint3D {
+        x: -50,
+        y: -50,
+        z: -50,
+    },
+    terminex: IPoint3D {
+        x: 50,
+        y: 50,
+        z: 50,
+    },
+};
 
-    fn from_str(point: &str) -> Result<Self> {
-        let parsed = point
-            .split(',')
-            .map(|x| Ok(x.parse()?))
-            .collect::<Result<Vec<i32>>>()?;
-
-        Ok(Point3D {
-            x: parsed[0],
-            y: parsed[1],
-            z: parsed[2],
-        })
-    }
-}
-
-impl fmt::Display for Point3D {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{},{},{}", self.x, self.y, self.z)
-    }
-}
-
-impl fmt::Debug for Point3D {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self)
-    }
-}
-
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
-struct Cuboid {
-    origin: Point3D,
-    terminex: Point3D,
-}
-
-impl Cuboid {
-    fn overlaps(&self, rhs: &Cuboid) -> bool {
-        !(self.terminex.x < rhs.origin.x
-            || rhs.terminex.x < self.origin.x
-            || self.terminex.y < rhs.origin.y
-            || rhs.terminex.y < self.origin.y
-            || self.terminex.z < rhs.origin.z
-            || rhs.terminex.z < self.origin.z)
-    }
-
-    // splits the cuboid in two at the x-coordinate
-    fn split_x(&self, x: i32) -> (Cuboid, Cuboid) {
-        if x < self.origin.x || x > self.terminex.x {
-            panic!(
-                "cannot split_x when x is not inside cuboid, x={}, origin={}, terminex={}",
-                x, self.origin, self.terminex
-            );
-        }
-
-        (
-            Cuboid {
-                origin: self.origin,
-                terminex: Point3D {
-                    x: x - 1,
-                    y: self.terminex.y,
-                    z: self.terminex.z,
-                },
-            },
-            Cuboid {
-                origin: Point3D {
-                    x,
-                    y: self.origin.y,
-                    z: self.origin.z,
-                },
-                terminex: self.terminex,
-            },
-        )
-    }
-
-    // splits the cuboid in two at the y-coordinate
-    fn split_y(&self, y: i32) -> (Cuboid, Cuboid) {
-        if y < self.origin.y || y > self.terminex.y {
-            panic!(
-                "cannot split_y when y is not inside cuboid, y={}, origin={}, terminex={}",
-                y, self.origin, self.terminex
-            );
-        }
-
-        (
-            Cuboid {
-                origin: self.origin,
-                terminex: Point3D {
-                    x: self.terminex.x,
-                    y: y - 1,
-                    z: self.terminex.z,
-                },
-            },
-            Cuboid {
-                origin: Point3D {
-                    x: self.origin.x,
-                    y,
-                    z: self.origin.z,
-                },
-                terminex: self.terminex,
-            },
-        )
-    }
-
-    // splits the cuboid in two at the y-coordinate
-    fn split_z(&self, z: i32) -> (Cuboid, Cuboid) {
-        if z < self.origin.z || z > self.terminex.z {
-            panic!(
-                "cannot split_z when z is not inside cuboid, z={}, origin={}, terminex={}",
-                z, self.origin, self.terminex
-            );
-        }
-
-        (
-            Cuboid {
-                origin: self.origin,
-                terminex: Point3D {
-                    x: self.terminex.x,
-                    y: self.terminex.y,
-                    z: z - 1,
-                },
-            },
-            Cuboid {
-                origin: Point3D {
-                    x: self.origin.x,
-                    y: self.origin.y,
-                    z,
-                },
-                terminex: self.terminex,
-            },
-        )
-    }
-
-    // returns the portion of rhs that is not contained by self as a set of cuboids
-    fn difference(&self, rhs: &Cuboid) -> Vec<Cuboid> {
-        let mut result = vec![];
-        let mut remainder = *rhs;
-
-        if remainder.origin.x < self.origin.x {
-            let (c1, c2) = remainder.split_x(self.origin.x);
-            result.push(c1);
-            remainder = c2;
-        }
-
-        if remainder.origin.y < self.origin.y {
-            let (c1, c2) = remainder.split_y(self.origin.y);
-            result.push(c1);
-            remainder = c2;
-        }
-
-        if remainder.origin.z < self.origin.z {
-            let (c1, c2) = remainder.split_z(self.origin.z);
-            result.push(c1);
-            remainder = c2;
-        }
-
-        if remainder.terminex.x > self.terminex.x {
-            let (c1, c2) = remainder.split_x(self.terminex.x + 1);
-            remainder = c1;
-            result.push(c2);
-        }
-
-        if remainder.terminex.y > self.terminex.y {
-            let (c1, c2) = remainder.split_y(self.terminex.y + 1);
-            remainder = c1;
-            result.push(c2);
-        }
-
-        if remainder.terminex.z > self.terminex.z {
-            let (c1, c2) = remainder.split_z(self.terminex.z + 1);
-            remainder = c1;
-            result.push(c2);
-        }
-
-        assert!(self.overlaps(&remainder));
-
-        result
-    }
-
-    // since reactor cores are discrete, volume is the number of contained points
-    fn volume(&self) -> u64 {
-        let width = self.terminex.x - self.origin.x + 1;
-        let depth = self.terminex.y - self.origin.y + 1;
-        let height = self.terminex.z - self.origin.z + 1;
-
-        width as u64 * depth as u64 * height as u64
-    }
-}
-
-impl FromStr for Cuboid {
-    type Err = Error;
-
-    fn from_str(ranges: &str) -> Result<Self> {
-        let bounds = ranges
-            .split(',')
-            .map(|x| {
-                let bounds = x[2..]
-                    .split("..")
-                    .map(|y| Ok(y.parse()?))
-                    .collect::<Result<Vec<i32>>>()?;
-                Ok((bounds[0], bounds[1]))
-            })
-            .collect::<Result<Vec<(i32, i32)>>>()?;
-
-        Ok(Cuboid {
-            origin: Point3D {
-                x: bounds[0].0,
-                y: bounds[1].0,
-                z: bounds[2].0,
-            },
-            terminex: Point3D {
-                x: bounds[0].1,
-                y: bounds[1].1,
-                z: bounds[2].1,
-            },
-        })
-    }
+fn parse_cuboid(ranges: &str) -> Result<ICuboid> {
+    // x=10..12,y=10..12,z=10..12
+    let [x0, x1, y0, y1, z0, z1] = ints_n(ranges)?;
+    Ok(ICuboid::new(ipt3(x0, y0, z0), ipt3(x1, y1, z1)))
 }
 
 enum Instruction {
-    On(Cuboid),
-    Off(Cuboid),
+    On(ICuboid),
+    Off(ICuboid),
 }
 
 impl Instruction {
@@ -288,12 +38,7 @@ impl Instruction {
             Off(c) => c,
         };
 
-        !(cuboid.origin.x < -50
-            || cuboid.origin.y < -50
-            || cuboid.origin.z < -50
-            || cuboid.terminex.x > 50
-            || cuboid.terminex.y > 50
-            || cuboid.terminex.z > 50)
+        INITIALIZATION_REGION.intersect(cuboid) == Some(*cuboid)
     }
 }
 
@@ -306,7 +51,7 @@ impl FromStr for Instruction {
         let (on_off, cuboid) = instruction
             .split_once(' ')
             .ok_or_else(|| anyhow!("invalid instruction format"))?;
-        let cuboid: Cuboid = cuboid.parse()?;
+        let cuboid = parse_cuboid(cuboid)?;
         match on_off {
             "on" => Ok(On(cuboid)),
             "off" => Ok(Off(cuboid)),
@@ -319,22 +64,29 @@ fn parse(contents: &str) -> Result<Vec<Instruction>> {
     contents.lines().map(|x| Ok(x.parse()?)).collect()
 }
 
-fn part1(instructions: &[Instruction]) -> Result<u64> {
-    let mut core = ReactorCore::new();
+fn process(core: &mut CuboidSet, instruction: &Instruction) {
+    match instruction {
+        On(cuboid) => core.insert(*cuboid),
+        Off(cuboid) => core.remove(cuboid),
+    }
+}
+
+fn part1(instructions: &[Instruction]) -> Result<i64> {
+    let mut core = CuboidSet::new();
     for instruction in instructions {
         if instruction.is_initialization() {
-            core.process(instruction);
+            process(&mut core, instruction);
         }
     }
-    Ok(core.volume_on())
+    Ok(core.volume())
 }
 
-fn part2(instructions: &[Instruction]) -> Result<u64> {
-    let mut core = ReactorCore::new();
+fn part2(instructions: &[Instruction]) -> Result<i64> {
+    let mut core = CuboidSet::new();
     for instruction in instructions {
-        core.process(instruction);
+        process(&mut core, instruction);
     }
-    Ok(core.volume_on())
+    Ok(core.volume())
 }
 
 #[cfg(test)]
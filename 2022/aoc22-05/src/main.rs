@@ -15,8 +15,11 @@ struct Problem {
 impl FromStr for Problem {
     type Err = Error;
     fn from_str(contents: &str) -> Result<Problem> {
-        let (port, moves) = parse_pair_by(contents, "\n\n", parse_untrimmed, parse_lines)?;
-        Ok(Problem { port, moves })
+        let mut sections = Sections::new(contents);
+        Ok(Problem {
+            port: sections.take_with(parse_untrimmed)?,
+            moves: sections.take_with(parse_lines)?,
+        })
     }
 }
 
@@ -67,31 +70,9 @@ impl FromStr for Port {
     type Err = Error;
 
     fn from_str(contents: &str) -> Result<Self, Self::Err> {
-        let mut bottom_up = contents.lines().rev();
-        let stack_count = bottom_up
-            .next()
-            .ok_or_else(|| anyhow!("malformed stack grid"))?
-            .len()
-            / 4
-            + 1;
-        let mut stacks = Vec::with_capacity(stack_count);
-        for _ in 0..stack_count {
-            stacks.push(vec![]);
-        }
-
-        for line in bottom_up {
-            for (pos, chunk) in line.as_bytes().chunks(4).enumerate() {
-                if chunk.len() > 1 && chunk[1] != b' ' {
-                    stacks[pos].push(
-                        char::from_u32(chunk[1].into())
-                            .ok_or_else(|| anyhow!("invalid crate character"))?
-                            as Crate,
-                    )
-                }
-            }
-        }
-
-        Ok(Port { stacks })
+        Ok(Port {
+            stacks: parse_column_stacks(contents, ColumnLayout::Footer)?,
+        })
     }
 }
 
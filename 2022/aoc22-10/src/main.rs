@@ -171,7 +171,7 @@ impl Solution for Problem {
 
         vm.run();
 
-        Ok(vm.display)
+        ocr(&vm.display)
     }
 }
 
@@ -194,12 +194,12 @@ mod tests {
     fn sample_part2() -> Result<()> {
         let problem = Problem::from_str(SAMPLE)?;
 
-        let result = problem.part2()?;
-
-        println!("{}", result);
-        println!("{}", EXPECTED_OUTPUT);
+        // the sample draws a test pattern instead of letters, so check the display part 2 reads
+        let mut vm = SpriteVM::new(&problem.instructions);
+        vm.run();
 
-        assert_eq!(EXPECTED_OUTPUT, result);
+        assert_eq!(EXPECTED_OUTPUT, vm.display);
+        assert!(problem.part2().is_err());
 
         Ok(())
     }
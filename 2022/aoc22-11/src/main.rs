@@ -41,21 +41,15 @@ impl FromStr for Monkey {
     type Err = Error;
 
     fn from_str(monkey: &str) -> Result<Self> {
-        let (_id, rest) = monkey.split_once('\n').ok_or(anyhow!("bad decision"))?;
-        let (items, rest) = rest.split_once('\n').ok_or(anyhow!("bad decision"))?;
-        let (prefix, items) = items.split_at(18);
-        if prefix != "  Starting items: " {
-            bail!("malformed monkey item list, prefix '{prefix}' not expected");
-        }
-        let (operation, decision) = rest.split_once('\n').ok_or(anyhow!("bad decision"))?;
+        let (_id, items, operation, decision) = scan!(
+            "Monkey {}:\n  Starting items: {}\n{}\n{}",
+            monkey.trim_end() => usize, String, Operation, Decision
+        )?;
 
         Ok(Monkey {
-            items: items
-                .split(", ")
-                .filter_map(|x| x.parse().ok())
-                .collect::<Vec<Item>>(),
-            operation: operation.parse()?,
-            decision: decision.parse()?,
+            items: items.parse_split(", ")?,
+            operation,
+            decision,
             inspection_count: 0,
         })
     }
@@ -92,17 +86,13 @@ impl FromStr for Operation {
     type Err = Error;
 
     fn from_str(operation: &str) -> Result<Self> {
-        let (prefix, parts) = operation.split_at(23);
-        if prefix != "  Operation: new = old " {
-            bail!("malformed operation, prefix '{prefix}' not expected");
-        }
-
-        let (operation, operand) = parts.split_once(' ').ok_or(anyhow!("bad decision"))?;
+        let (operation, operand) =
+            scan!("  Operation: new = old {} {}", operation => char, String)?;
         Ok(match operation {
-            "+" if operand == "old" => Operation::Double,
-            "+" => Operation::Plus(operand.parse()?),
-            "*" if operand == "old" => Operation::Square,
-            "*" => Operation::Times(operand.parse()?),
+            '+' if operand == "old" => Operation::Double,
+            '+' => Operation::Plus(operand.parse()?),
+            '*' if operand == "old" => Operation::Square,
+            '*' => Operation::Times(operand.parse()?),
             _ => bail!("Invalid operation"),
         })
     }
@@ -139,26 +129,10 @@ impl FromStr for Decision {
     type Err = Error;
 
     fn from_str(decision: &str) -> Result<Self> {
-        let (test, outcomes) = decision.split_once('\n').ok_or(anyhow!("bad decision"))?;
-        let (prefix, test) = test.split_at(21);
-        if prefix != "  Test: divisible by " {
-            bail!("malformed decision, prefix '{prefix}' not expected");
-        }
-        let (if_true, if_false) = outcomes.split_once('\n').ok_or(anyhow!("bad decision"))?;
-        let (prefix, if_true) = if_true.split_at(29);
-        if prefix != "    If true: throw to monkey " {
-            bail!("malformed decision, prefix '{prefix}' not expected");
-        }
-        let (prefix, if_false) = if_false.split_at(30);
-        if prefix != "    If false: throw to monkey " {
-            bail!("malformed decision, prefix '{prefix}' not expected");
-        }
-
-        Ok(Decision {
-            divisible_by: test.parse()?,
-            if_true: if_true.parse()?,
-            if_false: if_false.trim().parse()?,
-        })
+        scan!(
+            "  Test: divisible by {}\n    If true: throw to monkey {}\n    If false: throw to monkey {}",
+            decision.trim_end() => Decision { divisible_by: u32, if_true: usize, if_false: usize }
+        )
     }
 }
 
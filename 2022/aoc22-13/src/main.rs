@@ -11,21 +11,28 @@ fn main() -> Result<()> {
 
 #[derive(Eq, PartialEq, Clone)]
 enum PacketData {
-    List(String),
+    List(Vec<PacketData>),
     Integer(u32),
 }
 use PacketData::*;
 
+fn packet_data(input: &str) -> Parsed<'_, PacketData> {
+    number()
+        .map(Integer)
+        .or(delimited(
+            literal("["),
+            separated(packet_data, literal(",")),
+            literal("]"),
+        )
+        .map(List))
+        .parse_next(input)
+}
+
 impl FromStr for PacketData {
     type Err = Error;
 
     fn from_str(contents: &str) -> Result<PacketData> {
-        Ok(
-            match contents.chars().next().ok_or(anyhow!("missing data"))? {
-                '[' => List(contents.to_string()),
-                _ => Integer(contents.parse()?),
-            },
-        )
+        parse_with(contents, packet_data)
     }
 }
 
@@ -33,7 +40,16 @@ impl Display for PacketData {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Integer(i) => write!(f, "{i}"),
-            List(s) => write!(f, "{s}"),
+            List(items) => {
+                write!(f, "[")?;
+                for (ix, item) in items.iter().enumerate() {
+                    if ix > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{item}")?;
+                }
+                write!(f, "]")
+            }
         }
     }
 }
@@ -45,94 +61,13 @@ impl std::cmp::PartialOrd for PacketData {
 }
 impl Ord for PacketData {
     fn cmp(&self, other: &Self) -> Ordering {
+        // lists compare item by item, and a list that runs out of items first is smaller. That's
+        // exactly how Vec compares
         match (self, other) {
             (Integer(s), Integer(o)) => s.cmp(o),
-            (List(_), Integer(o)) => self.cmp(&List(format!("[{o}]"))),
-            (Integer(s), List(_)) => List(format!("[{s}]")).cmp(other),
-            (List(s), List(o)) => compare_list(s.as_str(), o.as_str()),
-        }
-    }
-}
-
-fn next_list_item(list: &str, pos: &mut usize) -> Option<PacketData> {
-    let start = *pos;
-    let mut open_brackets = 0;
-    if list.chars().nth(*pos) == Some(',') {
-        *pos += 1;
-    }
-    while *pos < list.len() {
-        match list.chars().nth(*pos) {
-            Some('[') => open_brackets += 1,
-            Some(',') => {
-                if open_brackets == 0 {
-                    break;
-                }
-            }
-            Some(']') => {
-                open_brackets -= 1;
-                if open_brackets < 0 {
-                    // end of outer list
-                    break;
-                }
-                if open_brackets == 0 {
-                    *pos += 1;
-                    break;
-                }
-            }
-            None => return None,
-            _ => {}
-        }
-        *pos += 1
-    }
-    let end = *pos;
-    // advance to next item
-    *pos += 1;
-    list.chars()
-        .skip(start)
-        .take(end - start)
-        .collect::<String>()
-        .parse()
-        .ok()
-}
-
-fn compare_list(left: &str, right: &str) -> Ordering {
-    // compare the first value of each list, then the second value, and so on.
-    // If the left list runs out of items first, the inputs are in the right order.
-    // If the right list runs out of items first, the inputs are not in the right order.
-    // If the lists are the same length and no comparison makes a decision about the order,
-    // continue checking the next part of the input.
-
-    if !left.starts_with('[') || !left.ends_with(']') {
-        panic!("invalid list");
-    }
-    if !right.starts_with('[') || !right.ends_with(']') {
-        panic!("invalid list");
-    }
-
-    // skip '['
-    let mut lpos = 1;
-    let mut rpos = 1;
-    loop {
-        match (
-            next_list_item(left, &mut lpos),
-            next_list_item(right, &mut rpos),
-        ) {
-            (Some(left_item), Some(right_item)) => {
-                let ord = left_item.cmp(&right_item);
-                if ord == Ordering::Equal {
-                    continue;
-                }
-                return left_item.cmp(&right_item);
-            }
-            (None, Some(_)) => {
-                return Ordering::Less;
-            }
-            (Some(_), None) => {
-                return Ordering::Greater;
-            }
-            (None, None) => {
-                return Ordering::Equal;
-            }
+            (List(_), Integer(o)) => self.cmp(&List(vec![Integer(*o)])),
+            (Integer(s), List(_)) => List(vec![Integer(*s)]).cmp(other),
+            (List(s), List(o)) => s.cmp(o),
         }
     }
 }
@@ -181,8 +116,8 @@ impl Solution for Problem {
             .iter()
             .flat_map(|Packet((left, right))| [left, right])
             .collect();
-        let two = List("[[2]]".to_string());
-        let six = List("[[6]]".to_string());
+        let two: PacketData = "[[2]]".parse()?;
+        let six: PacketData = "[[6]]".parse()?;
         all_packets.push(&two);
         all_packets.push(&six);
         all_packets.sort();
@@ -9,139 +9,28 @@ fn main() -> Result<()> {
     Problem::go()
 }
 
-trait RangeExtensions {
-    fn overlaps(&self, other: &Self) -> bool;
-    fn containing_range(&self, other: &Self) -> Self;
-    fn len(&self) -> usize;
-}
-
-impl RangeExtensions for RangeInclusive<i32> {
-    fn overlaps(&self, other: &Self) -> bool {
-        self.start() <= other.end() && self.end() >= other.start()
-    }
-
-    fn containing_range(&self, other: &Self) -> Self {
-        min(*self.start(), *other.start())..=max(*self.end(), *other.end())
-    }
-
-    fn len(&self) -> usize {
-        if self.is_empty() {
-            0
-        } else {
-            ((self.end() - self.start()) as usize) + 1
-        }
-    }
-}
-
-#[derive(Debug)]
-struct RowCoverage {
-    ranges: Vec<RangeInclusive<i32>>,
-}
-
 #[allow(clippy::reversed_empty_ranges)] // need an empty range on purpose
 const EMPTY_RANGE: RangeInclusive<i32> = 1..=0;
 
-impl RowCoverage {
-    fn include(&mut self, range: RangeInclusive<i32>) {
-        if range.is_empty() {
-            return;
-        }
-
-        let mut new_range = range;
-        let mut output_ranges = vec![];
-
-        for existing_range in self.ranges.drain(..) {
-            if new_range.is_empty() {
-                // we've already added the new range, just keep appending the existing ranges
-                output_ranges.push(existing_range);
-            } else if new_range.overlaps(&existing_range) {
-                // generate the union of the two ranges
-                new_range = new_range.containing_range(&existing_range)
-            } else if new_range.start() < existing_range.start() {
-                output_ranges.push(new_range);
-                new_range = EMPTY_RANGE.clone();
-                output_ranges.push(existing_range);
-            } else {
-                output_ranges.push(existing_range);
-            }
-        }
-        if !new_range.is_empty() {
-            output_ranges.push(new_range);
-        }
-        self.ranges = output_ranges;
-    }
-
-    fn first_possible_beacon_in_row(&self, from: i32, to: i32) -> Option<i32> {
-        let mut last_end = i32::MIN;
-        for range in &self.ranges {
-            if range.end() < &from {
-                continue;
-            }
-            if last_end >= from && range.start() > &(last_end + 1) {
-                return Some(last_end + 1);
-            }
-            if range.start() > &to {
-                break;
-            }
-            last_end = *range.end();
-        }
-
-        None
-    }
-}
-
-impl FromIterator<RangeInclusive<i32>> for RowCoverage {
-    fn from_iter<I: IntoIterator<Item = RangeInclusive<i32>>>(iter: I) -> Self {
-        let mut coverage = RowCoverage { ranges: vec![] };
-
-        for range in iter {
-            coverage.include(range);
-        }
-
-        coverage
-    }
-}
-
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
 struct Sensor {
     location: IPoint2D,
     beacon: IPoint2D,
 }
 
-struct ProblemPoint(IPoint2D);
-
-impl FromStr for ProblemPoint {
-    type Err = Error;
-
-    fn from_str(contents: &str) -> Result<ProblemPoint> {
-        // x={}, y={}
-        let (x, y) = grab_2(contents, ['=', ',', ' '], 1, 4)?;
-        Ok(ProblemPoint(IPoint2D { x, y }))
-    }
-}
-
 impl FromStr for Sensor {
     type Err = Error;
 
     fn from_str(contents: &str) -> Result<Sensor> {
-        // Sensor at {}: closest beacon is at x=-2, y=15
-        let (prefix, rest) = contents.split_at(10);
-        if prefix != "Sensor at " {
-            bail!("invalid prefix '{prefix}'");
-        }
-
-        if let Some((loc, rest)) = rest.split_once(':') {
-            let (prefix, dist) = rest.split_at(22);
-            if prefix != " closest beacon is at " {
-                bail!("Invalid prefix '{prefix}'")
-            }
-            Ok(Sensor {
-                location: loc.parse::<ProblemPoint>()?.0,
-                beacon: dist.parse::<ProblemPoint>()?.0,
-            })
-        } else {
-            bail!("no distance");
-        }
+        // Sensor at x=2, y=18: closest beacon is at x=-2, y=15
+        let [x, y, beacon_x, beacon_y] = ints_n(contents)?;
+        Ok(Sensor {
+            location: IPoint2D { x, y },
+            beacon: IPoint2D {
+                x: beacon_x,
+                y: beacon_y,
+            },
+        })
     }
 }
 
@@ -189,9 +78,9 @@ impl Problem {
             .sensors
             .iter()
             .map(|s| s.no_beacon_range_in_row(y, i32::MIN, i32::MAX))
-            .collect::<RowCoverage>();
+            .collect::<IntervalSet<i32>>();
 
-        coverage.ranges.iter().map(|x| x.len()).sum()
+        coverage.covered_len() as usize
     }
 
     fn tuning_frequency_for_missing_beacon_in_search_space(
@@ -219,13 +108,11 @@ impl Problem {
                         }
                     },
                 ))
-                .collect::<RowCoverage>();
+                .collect::<IntervalSet<i32>>();
 
-            match coverage.first_possible_beacon_in_row(0, search_boundary.y) {
-                None => {}
-                Some(x) => {
-                    return Some((x as i64) * 4000000 + (y as i64));
-                }
+            let first_gap = coverage.gaps(0..=search_boundary.x).next();
+            if let Some(gap) = first_gap {
+                return Some((gap.start as i64) * 4000000 + (y as i64));
             }
         }
 
@@ -287,10 +174,9 @@ mod tests {
             3329976..=4093060,
         ];
 
-        let coverage = RowCoverage::from_iter(ranges);
+        let coverage = IntervalSet::from_iter(ranges);
 
-        assert_eq!(coverage.ranges.len(), 1);
-        assert_eq!(coverage.ranges[0], -1432930..=4234952);
+        assert_eq!(coverage, IntervalSet::from(-1432930..=4234952));
 
         Ok(())
     }
@@ -12,75 +12,48 @@ fn parse_seeds(seeds: &str) -> Result<Vec<usize>> {
     if label != "seeds" {
         bail!("invalid seeds label '{label}'");
     }
-    parse_split(seeds, ' ')
+    uints(seeds)
 }
 
 struct GardenMap {
-    rules: Vec<MapRule>,
+    map: IntervalMap<usize>,
 }
 
 impl FromStr for GardenMap {
     type Err = Error;
 
     fn from_str(map: &str) -> Result<Self> {
-        let (_, map) = map.split_once('\n').ok_or_invalid()?;
-        Ok(GardenMap {
-            rules: parse_lines(map)?,
-        })
+        let mut interval_map = IntervalMap::new();
+        for rule in parse_lines::<MapRule>(map)? {
+            interval_map.insert(rule.source_range, rule.dest_start)?;
+        }
+        Ok(GardenMap { map: interval_map })
     }
 }
 
 impl GardenMap {
     fn dest(&self, source: &usize) -> usize {
-        for rule in &self.rules {
-            if rule.source_range.contains(source) {
-                return rule.dest_range.start + (source - rule.source_range.start);
-            }
-        }
-        *source
+        self.map.get(*source)
     }
 
-    fn dest_range(&self, source: Vec<Range<usize>>) -> Vec<Range<usize>> {
-        let mut source = source;
-        let mut dest = vec![];
-        for rule in &self.rules {
-            let mut unmapped = vec![];
-            for source_range in &source {
-                if let Some(intersecting_range) = rule.source_range.intersect(source_range) {
-                    let delta = intersecting_range.start - rule.source_range.start;
-                    let mut difference = source_range.difference(&intersecting_range);
-                    dest.push(
-                        (rule.dest_range.start + delta)
-                            ..(rule.dest_range.start + delta + intersecting_range.len()),
-                    );
-                    unmapped.append(&mut difference);
-                } else {
-                    unmapped.push(source_range.clone());
-                }
-            }
-            source = unmapped;
-        }
-        dest.append(&mut source);
-        dest
+    fn dest_range(&self, source: IntervalSet<usize>) -> IntervalSet<usize> {
+        self.map.map_set(&source)
     }
 }
 
 struct MapRule {
     source_range: Range<usize>,
-    dest_range: Range<usize>,
+    dest_start: usize,
 }
 
 impl FromStr for MapRule {
     type Err = Error;
 
     fn from_str(rule: &str) -> Result<Self> {
-        let mut parts = rule.split(' ');
-        let dest_start = parts.next().ok_or_invalid()?.parse_wrapped()?;
-        let source_start = parts.next().ok_or_invalid()?.parse_wrapped()?;
-        let range_len: usize = parts.next().ok_or_invalid()?.parse_wrapped()?;
+        let [dest_start, source_start, range_len]: [usize; 3] = ints_n(rule)?;
         Ok(MapRule {
             source_range: source_start..(source_start + range_len),
-            dest_range: dest_start..(dest_start + range_len),
+            dest_start,
         })
     }
 }
@@ -100,16 +73,16 @@ impl FromStr for Problem {
     type Err = Error;
 
     fn from_str(contents: &str) -> Result<Self> {
-        let mut groups = contents.split("\n\n");
+        let mut sections = Sections::new(contents);
         Ok(Self {
-            seeds: parse_seeds(groups.next().ok_or_invalid()?)?,
-            seed_to_soil_map: groups.next().ok_or_invalid()?.parse_wrapped()?,
-            soil_to_fertilizer_map: groups.next().ok_or_invalid()?.parse_wrapped()?,
-            fertilizer_to_water_map: groups.next().ok_or_invalid()?.parse_wrapped()?,
-            water_to_light_map: groups.next().ok_or_invalid()?.parse_wrapped()?,
-            light_to_temparature_map: groups.next().ok_or_invalid()?.parse_wrapped()?,
-            temperature_to_humidity_map: groups.next().ok_or_invalid()?.parse_wrapped()?,
-            humitity_to_location_map: groups.next().ok_or_invalid()?.parse_wrapped()?,
+            seeds: sections.take_with(parse_seeds)?,
+            seed_to_soil_map: sections.named("seed-to-soil map")?,
+            soil_to_fertilizer_map: sections.named("soil-to-fertilizer map")?,
+            fertilizer_to_water_map: sections.named("fertilizer-to-water map")?,
+            water_to_light_map: sections.named("water-to-light map")?,
+            light_to_temparature_map: sections.named("light-to-temperature map")?,
+            temperature_to_humidity_map: sections.named("temperature-to-humidity map")?,
+            humitity_to_location_map: sections.named("humidity-to-location map")?,
         })
     }
 }
@@ -148,7 +121,7 @@ impl Problem {
     }
 
     fn lowest_location_range<T: Iterator<Item = Range<usize>>>(&self, seeds: T) -> Result<usize> {
-        let seeds: Vec<_> = seeds.collect();
+        let seeds: IntervalSet<_> = seeds.collect();
         let soil = self.seed_to_soil_map.dest_range(seeds);
         let fertilizer = self.soil_to_fertilizer_map.dest_range(soil);
         let water = self.fertilizer_to_water_map.dest_range(fertilizer);
@@ -158,9 +131,9 @@ impl Problem {
         let location = self.humitity_to_location_map.dest_range(humidity);
 
         location
-            .into_iter()
+            .ranges()
+            .first()
             .map(|range| range.start)
-            .min()
             .ok_or_invalid()
     }
 }
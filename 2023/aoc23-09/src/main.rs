@@ -8,7 +8,7 @@ fn main() -> Result<()> {
 }
 
 struct Sensor {
-    change_stack: Vec<Vec<i32>>,
+    history: Vec<i32>,
 }
 
 struct Problem {
@@ -19,48 +19,19 @@ impl FromStr for Sensor {
     type Err = Error;
 
     fn from_str(contents: &str) -> Result<Self> {
-        let mut change_stack: Vec<Vec<i32>> = vec![contents.parse_split_whitespace()?];
-
-        loop {
-            let next_line: Vec<i32> = change_stack
-                .last()
-                .unwrap()
-                .windows(2)
-                .map(|w| w[1] - w[0])
-                .collect();
-
-            if next_line.iter().any(|v| *v != 0) {
-                change_stack.push(next_line);
-            } else {
-                break;
-            }
-        }
-
-        Ok(Self { change_stack })
+        Ok(Self {
+            history: contents.parse_split_whitespace()?,
+        })
     }
 }
 
 impl Sensor {
-    fn predict_next(&self) -> i32 {
-        let mut last_change = 0;
-
-        for i in (0..self.change_stack.len()).rev() {
-            let line = &self.change_stack[i];
-            last_change += line.last().unwrap();
-        }
-
-        last_change
+    fn predict_next(&self) -> Result<i32> {
+        extrapolate_next(&self.history)
     }
 
-    fn predict_prev(&self) -> i32 {
-        let mut prev_change = 0;
-
-        for i in (0..self.change_stack.len()).rev() {
-            let line = &self.change_stack[i];
-            prev_change = line[0] - prev_change;
-        }
-
-        prev_change
+    fn predict_prev(&self) -> Result<i32> {
+        extrapolate_prev(&self.history)
     }
 }
 
@@ -79,11 +50,11 @@ impl Solution for Problem {
     type Part2 = i32;
 
     fn part1(&mut self) -> Result<Self::Part1> {
-        Ok(self.sensors.iter().map(|s| s.predict_next()).sum())
+        self.sensors.iter().map(|s| s.predict_next()).sum()
     }
 
     fn part2(&self) -> Result<Self::Part2> {
-        Ok(self.sensors.iter().map(|s| s.predict_prev()).sum())
+        self.sensors.iter().map(|s| s.predict_prev()).sum()
     }
 }
 
@@ -123,7 +123,7 @@ impl Solution for Problem {
             }
         }
 
-        let loop_area = shoelace_loop_area(
+        let loop_area = shoelace_loop_area::<i32>(
             corners
                 .iter()
                 .map(|pt| IPoint2D::try_from(*pt))
@@ -155,6 +155,35 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn a_star_matches_dijkstra() -> Result<()> {
+        let problem = Problem::from_str(SAMPLE)?;
+        let bottom_right = problem.map.bounds.bottom_right();
+
+        for (min_steps, max_steps) in [(1, 3), (4, 10)] {
+            let expected = dijkstra(
+                LavaPathState::new(),
+                |state| problem.next_moves(state, min_steps, max_steps),
+                |state| state.current_location == bottom_right,
+            )
+            .map(|state| state.heat_loss)
+            .ok_or_invalid()?;
+            // every block loses at least 1 heat, so manhattan distance is admissible
+            let found = a_star(
+                LavaPathState::new(),
+                |state| problem.next_moves(state, min_steps, max_steps),
+                |state| state.current_location.manhattan_distance(bottom_right),
+                |state| state.current_location == bottom_right,
+            )
+            .map(|state| state.heat_loss)
+            .ok_or_invalid()?;
+
+            assert_eq!(expected, found);
+        }
+
+        Ok(())
+    }
+
     const SAMPLE: &str = "\
 2413432311323
 3215453535623
@@ -91,7 +91,7 @@ where
     // we calculated the area, but because it was calculated from the center of the borders half of
     // the boundary length is left off of the final result. Then there are also 4 corners that are
     // not accounted for, so add those in
-    shoelace_loop_area_64(vertices) + boundary_length / 2 + 1
+    shoelace_loop_area::<i64>(vertices) + boundary_length / 2 + 1
 }
 
 impl Solution for Problem {
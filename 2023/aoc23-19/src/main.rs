@@ -1,4 +1,4 @@
-use std::{collections::HashMap, ops::RangeInclusive, str::FromStr};
+use std::{collections::HashMap, str::FromStr};
 
 use anyhow::*;
 use aoc_common::*;
@@ -50,33 +50,34 @@ impl FromStr for Problem {
     type Err = Error;
 
     fn from_str(contents: &str) -> Result<Self> {
-        let (workflows, parts) = contents.split_once("\n\n").ok_or_invalid()?;
+        let mut sections = Sections::new(contents);
         Ok(Self {
-            workflows: workflows
-                .lines()
-                .map(|line| -> Result<(String, Vec<Rule>)> {
-                    let (name, rules) = line.split_once('{').ok_or_invalid()?;
-
-                    Ok((
-                        name.to_string(),
-                        rules.trim_end_matches('}').parse_split(',')?,
-                    ))
-                })
-                .collect::<Result<HashMap<_, _>>>()?,
-            parts: parts
-                .lines()
-                .map(|part| -> Result<HashMap<Category, usize>> {
-                    Ok(part
-                        .trim_start_matches('{')
-                        .trim_end_matches('}')
-                        .split(',')
-                        .map(|cat_val| -> Result<(Category, usize)> {
-                            let (category, value) = cat_val.split_once('=').ok_or_invalid()?;
-                            Ok((category.parse_wrapped()?, value.parse_wrapped()?))
-                        })
-                        .collect::<Result<HashMap<_, _>>>()?)
-                })
-                .collect::<Result<Vec<HashMap<_, _>>>>()?,
+            workflows: sections.take_with(|workflows| {
+                workflows
+                    .lines()
+                    .map(|line| -> Result<(String, Vec<Rule>)> {
+                        let (name, rules) = scan!("{}{{{}}}", line => String, String)?;
+
+                        Ok((name, rules.parse_split(',')?))
+                    })
+                    .collect()
+            })?,
+            parts: sections.take_with(|parts| {
+                parts
+                    .lines()
+                    .map(|part| -> Result<HashMap<Category, usize>> {
+                        let (x, m, a, s) =
+                            scan!("{{x={},m={},a={},s={}}}", part => usize, usize, usize, usize)?;
+
+                        Ok(HashMap::from([
+                            (XCool, x),
+                            (Musical, m),
+                            (Aero, a),
+                            (Shiny, s),
+                        ]))
+                    })
+                    .collect()
+            })?,
         })
     }
 }
@@ -157,17 +158,16 @@ impl Solution for Problem {
         Ok(self.count_solutions(
             "in",
             HashMap::from([
-                (XCool, 1..=4000),
-                (Musical, 1..=4000),
-                (Aero, 1..=4000),
-                (Shiny, 1..=4000),
+                (XCool, IntervalSet::from(1..=4000)),
+                (Musical, IntervalSet::from(1..=4000)),
+                (Aero, IntervalSet::from(1..=4000)),
+                (Shiny, IntervalSet::from(1..=4000)),
             ]),
         ))
     }
 }
 
-type Range = RangeInclusive<i32>;
-type Ranges = HashMap<Category, Range>;
+type Ranges = HashMap<Category, IntervalSet<usize>>;
 
 impl Problem {
     fn is_accepted(&self, part: &&HashMap<Category, usize>) -> bool {
@@ -234,49 +234,42 @@ impl Rule {
             All => (Some((&self.target, ranges.clone())), None),
 
             LessThan(category, rhs) => {
-                // figure out if the rule does or does not match for all of the range, or part of it
-                let range = &ranges[&category];
-                let rhs: i32 = rhs.try_into().unwrap();
-                if range.contains(&rhs) {
-                    // split the range into one that matches and one that doesn't
-                    // < does not include rhs
-                    let r1 = override_range(ranges, category, (*range.start())..=(rhs - 1));
-                    let r2 = override_range(ranges, category, rhs..=(*range.end()));
-                    (Some((&self.target, r1)), Some(r2))
-                } else if rhs <= *range.start() {
-                    // never matches
-                    (None, Some(ranges.clone()))
-                } else {
-                    // always matches
-                    (Some((&self.target, ranges.clone())), None)
-                }
+                // < does not include rhs
+                let (matched, unmatched) = ranges[&category].split_at(rhs);
+                self.split_on(ranges, category, matched, unmatched)
             }
             GreaterThan(category, rhs) => {
-                let range = &ranges[&category];
-                let rhs: i32 = rhs.try_into().unwrap();
-                if range.contains(&rhs) {
-                    // in this case, r1 does include rhs, but r2 does not
-                    let r1 = override_range(ranges, category, (*range.start())..=rhs);
-                    let r2 = override_range(ranges, category, (rhs + 1)..=(*range.end()));
-                    (Some((&self.target, r2)), Some(r1))
-                } else if rhs >= *range.end() {
-                    (None, Some(ranges.clone()))
-                } else {
-                    (Some((&self.target, ranges.clone())), None)
-                }
+                // > does not include rhs either, so it stays with the values that don't match
+                let (unmatched, matched) = ranges[&category].split_at(rhs + 1);
+                self.split_on(ranges, category, matched, unmatched)
             }
         }
     }
+
+    // either side of the split can be empty, in which case it's dropped
+    fn split_on(
+        &self,
+        ranges: &Ranges,
+        category: Category,
+        matched: IntervalSet<usize>,
+        unmatched: IntervalSet<usize>,
+    ) -> SplitRanges<'_> {
+        (
+            (!matched.is_empty())
+                .then(|| (&self.target, override_range(ranges, category, matched))),
+            (!unmatched.is_empty()).then(|| override_range(ranges, category, unmatched)),
+        )
+    }
 }
 
-fn override_range(ranges: &Ranges, category: Category, range: Range) -> Ranges {
+fn override_range(ranges: &Ranges, category: Category, range: IntervalSet<usize>) -> Ranges {
     let mut ranges = ranges.clone();
     ranges.insert(category, range);
     ranges
 }
 
 fn range_combinations(ranges: Ranges) -> usize {
-    ranges.values().map(|range| range.clone().count()).product()
+    ranges.values().map(IntervalSet::covered_len).product()
 }
 
 #[cfg(test)]
@@ -1,4 +1,4 @@
-use std::{collections::VecDeque, str::FromStr};
+use std::str::FromStr;
 
 use anyhow::*;
 use aoc_common::*;
@@ -43,21 +43,15 @@ impl FromStr for Problem {
     fn from_str(contents: &str) -> Result<Self> {
         let map: Grid2D<Space> = Grid2D::from_char_str(contents)?;
         let cache_capacity = map.bounds.width * map.bounds.height;
-        Ok(Self {
+        let mut problem = Self {
             map,
             space_cache: FnvHashMap::with_capacity_and_hasher(cache_capacity, Default::default()),
-        })
+        };
+        problem.prime_space_cache();
+        Ok(problem)
     }
 }
 
-const CENTER: Point2D = Point2D { x: 65, y: 65 };
-const CORNERS: [Point2D; 4] = [
-    Point2D { x: 0, y: 0 },
-    Point2D { x: 130, y: 0 },
-    Point2D { x: 0, y: 130 },
-    Point2D { x: 130, y: 130 },
-];
-
 impl Problem {
     fn prime_space_cache(&mut self) {
         // for every location in the grid, figure out where next steps would lead
@@ -125,51 +119,6 @@ impl Problem {
         }
         vec![]
     }
-
-    fn count_even_and_odd_reachable(
-        &self,
-        from: &[Point2D],
-        limit: usize,
-    ) -> (usize, usize, usize, usize) {
-        let mut map = self.map.clone();
-        let mut todo = VecDeque::new();
-
-        let mut even_inner = 0;
-        let mut even_outer = 0;
-        let mut odd_inner = 0;
-        let mut odd_outer = 0;
-
-        for &start in from {
-            map[start] = Rock;
-            todo.push_back((start, 0));
-        }
-
-        while let Some((position, cost)) = todo.pop_front() {
-            if cost % 2 == 1 {
-                if position.manhattan_distance(CENTER) <= 65 {
-                    odd_inner += 1;
-                } else {
-                    odd_outer += 1;
-                }
-            } else if cost <= 64 {
-                even_inner += 1;
-            } else {
-                even_outer += 1;
-            }
-
-            if cost < limit {
-                for next in position.cardinal_neighbors(map.bounds) {
-                    if map[next] != Rock {
-                        todo.push_back((next, cost + 1));
-                        // don't count this location again
-                        map[next] = Rock;
-                    }
-                }
-            }
-        }
-
-        (even_inner, even_outer, odd_inner, odd_outer)
-    }
 }
 
 impl Solution for Problem {
@@ -177,34 +126,23 @@ impl Solution for Problem {
     type Part2 = usize;
 
     fn part1(&mut self) -> Result<Self::Part1> {
-        self.prime_space_cache();
         Ok(self.count_reachable_in_steps(&[64])[0])
     }
 
     fn part2(&self) -> Result<Self::Part2> {
-        // geometric solution
-        // determine the # of blocks reachable via even and odd number of steps
-        // then, given x = number of steps
-        //             a = # of blocks reachable on even steps in a single grid
-        //             b = # of blocks reachable on odd steps in a single grid
-        //             c = # of blocks reachable from the corners on even steps, within 65
-        //             manhattan distanc
-        //             d = # of blocks reachable from the middle on odd steps, within 65 manhattan
-        //             distance
-        // reachable = a*x^2 + b*(x+1)^2 + c*n - d*(n+1)
-
-        let (even_inner, even_outer, odd_inner, odd_outer) =
-            self.count_even_and_odd_reachable(&[CENTER], 130);
-        let evens = even_inner + even_outer;
-        let odds = odd_inner + odd_outer;
-        let remove_corners = odd_outer;
-
-        let (even_from_corners, ..) = self.count_even_and_odd_reachable(&CORNERS, 64);
-
-        let x = 202300;
-        let part_two = (x * x * evens) + ((x + 1) * (x + 1) * odds) + (x * even_from_corners)
-            - ((x + 1) * remove_corners);
-        Ok(part_two)
+        // the start is in the middle of a clear row and column, so after reaching the edge of
+        // the first copy of the map, the reachable area grows by a copy of the map in each
+        // direction every width steps. The count is then a quadratic in the number of copies
+        // crossed, so count the first few and extrapolate. Three counts fit any quadratic, the
+        // fourth checks that it really is one
+        let width = self.map.bounds.width;
+        let to_edge = width / 2;
+        let steps: Vec<usize> = (0..4).map(|copies| to_edge + copies * width).collect();
+        let samples = self.count_reachable_in_steps(&steps);
+        let reachable = detect_polynomial(&samples, 2)?
+            .ok_or_else(|| anyhow!("reachable counts {samples:?} aren't quadratic"))?;
+
+        reachable.evaluate((26501365 - to_edge) / width)
     }
 }
 
@@ -34,49 +34,12 @@ impl FromStr for Problem {
 }
 
 impl Problem {
-    fn settle(&mut self) {
-        // we've already sorted by min z, so looping from bottom to top should allow us to settle
-        let mut settled: Vec<Cuboid> = vec![];
-        for (brick_index, brick) in self.bricks.iter().enumerate() {
-            let x_range = brick.x_range();
-            let y_range = brick.y_range();
-            let min_z = brick.min_z();
-            let mut shift_by = 0;
-
-            loop {
-                let z_below = min_z - shift_by - 1;
-                let settled_on: Vec<_> = settled
-                    .iter()
-                    .enumerate()
-                    .filter_map(|(brick_below_index, brick_below)| {
-                        let other_x = brick_below.x_range();
-                        let other_y = brick_below.y_range();
-                        let other_z = brick_below.z_range();
-                        if other_x.overlaps(&x_range)
-                            && other_y.overlaps(&y_range)
-                            && other_z.contains(&z_below)
-                        {
-                            Some(brick_below_index)
-                        } else {
-                            None
-                        }
-                    })
-                    .collect();
-
-                if settled_on.is_empty() && z_below > 0 {
-                    shift_by += 1;
-                } else {
-                    for supporting_brick in &settled_on {
-                        self.supports[*supporting_brick].push(brick_index);
-                    }
-                    self.supported_by[brick_index] = settled_on;
-                    break;
-                }
-            }
-            settled.push(brick.shift_down_by(shift_by));
-        }
-
-        self.bricks = settled;
+    fn settle(&mut self) -> Result<()> {
+        let graph = settle_bricks(&self.bricks, 1)?;
+        self.bricks = graph.bricks;
+        self.supported_by = graph.supported_by;
+        self.supports = graph.supports;
+        Ok(())
     }
 
     fn count_chain_reaction(&self, brick_to_disintegrate: usize) -> usize {
@@ -110,7 +73,7 @@ impl Solution for Problem {
     type Part2 = usize;
 
     fn part1(&mut self) -> Result<Self::Part1> {
-        self.settle();
+        self.settle()?;
 
         Ok((0..self.bricks.len())
             .filter(|brick_ix| {
@@ -146,7 +109,7 @@ mod tests {
     #[test]
     fn sample_part2() -> Result<()> {
         let mut problem = Problem::from_str(SAMPLE)?;
-        problem.settle();
+        problem.settle()?;
 
         assert_eq!(6, problem.count_chain_reaction(0));
         assert_eq!(0, problem.count_chain_reaction(1));
@@ -2,48 +2,11 @@ use std::str::FromStr;
 
 use anyhow::*;
 use aoc_common::*;
-use ndarray::*;
-use ndarray_linalg::*;
 
 fn main() -> Result<()> {
     Problem::go()
 }
 
-// y=ax + b
-#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
-struct FPoint2D {
-    x: f64,
-    y: f64,
-}
-
-#[derive(Clone, Debug, PartialEq)]
-struct LineSegment2D {
-    slope: f64,
-    y_intercept: f64,
-    min_x: Option<f64>,
-    max_x: Option<f64>,
-}
-
-impl LineSegment2D {
-    fn intersect(&self, other: &LineSegment2D) -> Option<FPoint2D> {
-        if self.slope == other.slope {
-            None
-        } else {
-            let x = (other.y_intercept - self.y_intercept) / (self.slope - other.slope);
-            let y = self.slope * x + self.y_intercept;
-            if self.min_x.is_some_and(|min_x| x < min_x)
-                || other.min_x.is_some_and(|min_x| x < min_x)
-                || self.max_x.is_some_and(|max_x| x > max_x)
-                || other.max_x.is_some_and(|max_x| x > max_x)
-            {
-                None
-            } else {
-                Some(FPoint2D { x, y })
-            }
-        }
-    }
-}
-
 #[derive(Clone, Copy, Debug, PartialEq, PartialOrd, Eq, Ord, Hash)]
 struct Hail {
     position: Point3D,
@@ -78,24 +41,25 @@ impl FromStr for Hail {
 }
 
 impl Hail {
-    fn as_line_segment(&self) -> LineSegment2D {
-        let x: f64 = self.position.x as f64;
-        let y: f64 = self.position.y as f64;
-        let dx: f64 = self.velocity.dx as f64;
-        let dy: f64 = self.velocity.dy as f64;
-
-        let slope = dy / dx;
-        // y = slope*x + y_intercept
-        // y_intercept = y - slope * x
-        let y_intercept = y - slope * x;
-        let min_x = if dx >= 0.0 { Some(x) } else { None };
-        let max_x = if dx <= 0.0 { Some(x) } else { None };
-
-        LineSegment2D {
-            slope,
-            y_intercept,
-            min_x,
-            max_x,
+    fn position_i128(&self) -> [i128; 3] {
+        [self.position.x, self.position.y, self.position.z].map(|v| v as i128)
+    }
+
+    fn velocity_i128(&self) -> [i128; 3] {
+        [self.velocity.dx, self.velocity.dy, self.velocity.dz].map(i128::from)
+    }
+
+    // the hail's path, ignoring z
+    fn path_xy(&self) -> Line2D {
+        Line2D {
+            origin: IVector2D {
+                dx: self.position.x as i64,
+                dy: self.position.y as i64,
+            },
+            direction: IVector2D {
+                dx: self.velocity.dx,
+                dy: self.velocity.dy,
+            },
         }
     }
 }
@@ -108,23 +72,21 @@ impl Problem {
             .collect()
     }
 
-    fn count_intersections(&self, min_xy: f64, max_xy: f64) -> usize {
-        let lines: Vec<_> = self
-            .hail
-            .iter()
-            .map(|hail| hail.as_line_segment())
-            .collect();
+    fn count_intersections(&self, min_xy: i64, max_xy: i64) -> Result<usize> {
+        let paths: Vec<_> = self.hail.iter().map(|hail| hail.path_xy()).collect();
 
-        self.hail_index_pairs()
-            .into_iter()
-            .filter_map(|(i0, i1)| lines[i0].intersect(&lines[i1]))
-            .filter(|intersection| {
-                intersection.x >= min_xy
-                    && intersection.y >= min_xy
-                    && intersection.x <= max_xy
-                    && intersection.y <= max_xy
-            })
-            .count()
+        let mut count = 0;
+        for (i0, i1) in self.hail_index_pairs() {
+            // paths only count if they cross in the future for both hailstones
+            let intersection = paths[i0].intersect(&paths[i1])?;
+            if intersection
+                .ray_crossing()
+                .is_some_and(|crossing| within_box(crossing, [min_xy; 2], [max_xy; 2]))
+            {
+                count += 1;
+            }
+        }
+        Ok(count)
     }
 }
 
@@ -133,7 +95,7 @@ impl Solution for Problem {
     type Part2 = usize;
 
     fn part1(&mut self) -> Result<Self::Part1> {
-        Ok(self.count_intersections(200_000_000_000_000.0, 400_000_000_000_000.0))
+        self.count_intersections(200_000_000_000_000, 400_000_000_000_000)
     }
 
     fn part2(&self) -> Result<Self::Part2> {
@@ -147,88 +109,99 @@ impl Solution for Problem {
         //   math
         //
         // My try at the linear matrices wasn't successful since it's been 15 years since I took
-        // linear algebra, so I borrowed matrices from someone else's solution
-        let p0 = self.hail[0].position.to_f64_vec();
-        let v0 = self.hail[0].velocity.to_f64_vec();
-        let p1 = self.hail[1].position.to_f64_vec();
-        let v1 = self.hail[1].velocity.to_f64_vec();
-        let p2 = self.hail[2].position.to_f64_vec();
-        let v2 = self.hail[2].velocity.to_f64_vec();
-
-        let a: Array2<f64> = array![
+        // linear algebra, so I borrowed matrices from someone else's solution. The coefficients
+        // fit in an i128, but solving needs bigger intermediate values than that
+        let [p0, p1, p2] = [0, 1, 2].map(|ix| self.hail[ix].position_i128());
+        let [v0, v1, v2] = [0, 1, 2].map(|ix| self.hail[ix].velocity_i128());
+
+        let a: [[i128; 6]; 6] = [
             [
                 -(v0[1] - v1[1]),
                 v0[0] - v1[0],
-                0.0,
+                0,
                 p0[1] - p1[1],
                 -(p0[0] - p1[0]),
-                0.0
+                0,
             ],
             [
                 -(v0[1] - v2[1]),
                 v0[0] - v2[0],
-                0.0,
+                0,
                 p0[1] - p2[1],
                 -(p0[0] - p2[0]),
-                0.0
+                0,
             ],
             [
-                0.0,
+                0,
                 -(v0[2] - v1[2]),
                 v0[1] - v1[1],
-                0.0,
+                0,
                 p0[2] - p1[2],
-                -(p0[1] - p1[1])
+                -(p0[1] - p1[1]),
             ],
             [
-                0.0,
+                0,
                 -(v0[2] - v2[2]),
                 v0[1] - v2[1],
-                0.0,
+                0,
                 p0[2] - p2[2],
-                -(p0[1] - p2[1])
+                -(p0[1] - p2[1]),
             ],
             [
                 -(v0[2] - v1[2]),
-                0.0,
+                0,
                 v0[0] - v1[0],
                 p0[2] - p1[2],
-                0.0,
-                -(p0[0] - p1[0])
+                0,
+                -(p0[0] - p1[0]),
             ],
             [
                 -(v0[2] - v2[2]),
-                0.0,
+                0,
                 v0[0] - v2[0],
                 p0[2] - p2[2],
-                0.0,
-                -(p0[0] - p2[0])
-            ]
+                0,
+                -(p0[0] - p2[0]),
+            ],
         ];
 
-        let b: Array1<f64> = array![
+        let b: [i128; 6] = [
             (p0[1] * v0[0] - p1[1] * v1[0]) - (p0[0] * v0[1] - p1[0] * v1[1]),
             (p0[1] * v0[0] - p2[1] * v2[0]) - (p0[0] * v0[1] - p2[0] * v2[1]),
             (p0[2] * v0[1] - p1[2] * v1[1]) - (p0[1] * v0[2] - p1[1] * v1[2]),
             (p0[2] * v0[1] - p2[2] * v2[1]) - (p0[1] * v0[2] - p2[1] * v2[2]),
             (p0[2] * v0[0] - p1[2] * v1[0]) - (p0[0] * v0[2] - p1[0] * v1[2]),
-            (p0[2] * v0[0] - p2[2] * v2[0]) - (p0[0] * v0[2] - p2[0] * v2[2])
+            (p0[2] * v0[0] - p2[2] * v2[0]) - (p0[0] * v0[2] - p2[0] * v2[2]),
         ];
 
-        let solution = a.solve_into(b)?;
+        let a: Vec<Vec<BigInt>> = a
+            .iter()
+            .map(|row| row.iter().copied().map(BigInt::from).collect())
+            .collect();
+        let b: Vec<BigInt> = b.into_iter().map(BigInt::from).collect();
+
+        let LinearSolution::Unique(solution) = solve_integer_system(&a, &b)? else {
+            bail!("no single rock trajectory hits all the hail");
+        };
+        let solution = solution
+            .iter()
+            .map(|value| {
+                value
+                    .to_integer()
+                    .ok_or_else(|| anyhow!("rock trajectory {value} isn't a whole number"))
+            })
+            .collect::<Result<Vec<_>>>()?;
 
         let thrown_stone = Hail {
-            position: solution
-                .slice(s![0..3])
-                .into_iter()
-                .map(|f| f.round() as usize)
-                .collect::<Vec<usize>>()
+            position: solution[0..3]
+                .iter()
+                .map(usize::try_from)
+                .collect::<std::result::Result<Vec<usize>, _>>()?
                 .try_into()?,
-            velocity: solution
-                .slice(s![3..6])
-                .into_iter()
-                .map(|f| f.round() as i64)
-                .collect::<Vec<i64>>()
+            velocity: solution[3..6]
+                .iter()
+                .map(i64::try_from)
+                .collect::<std::result::Result<Vec<i64>, _>>()?
                 .try_into()?,
         };
 
@@ -244,7 +217,7 @@ mod tests {
     fn sample_part1() -> Result<()> {
         let problem = Problem::from_str(SAMPLE)?;
 
-        let result = problem.count_intersections(7.0, 27.0);
+        let result = problem.count_intersections(7, 27)?;
 
         assert_eq!(2, result);
 
@@ -15,17 +15,8 @@ impl FromStr for Problem {
     type Err = Error;
 
     fn from_str(contents: &str) -> Result<Self> {
-        let mut graph = UnweightedGraphX::default();
-        for line in contents.lines() {
-            let (node, connections) = line.split_once(": ").ok_or_invalid()?;
-            graph.add_edges_bidi(
-                node.to_string(),
-                connections.split(' ').map(|name| name.to_string()),
-            );
-        }
         Ok(Self {
-            // node_names: node_names.0,
-            graph,
+            graph: UnweightedGraphX::from_adjacency_str(contents, false)?,
         })
     }
 }
@@ -54,6 +45,7 @@ impl Solution for Problem {
                 .into_iter()
                 .map(|p| p.to_owned())
                 .collect::<Vec<_>>();
+            // the path runs from start to end inclusive, so this cuts every edge along it
             for edge in path.windows(2) {
                 self.graph.disconnect_bidi(edge[0], edge[1]);
             }
@@ -1,5 +1,3 @@
-use std::str::FromStr;
-
 use anyhow::*;
 use aoc_common::*;
 
@@ -14,16 +12,24 @@ struct Problem {
     list2: Vec<u32>,
 }
 
-impl FromStr for Problem {
-    type Err = Error;
-
-    fn from_str(contents: &str) -> Result<Self> {
+impl ParseInput for Problem {
+    fn parse_input(input: &[u8]) -> Result<Self> {
         let mut list1 = Vec::new();
         let mut list2 = Vec::new();
-        for line in contents.lines() {
-            let (a, b) = line.split_once("   ").ok_or_invalid()?;
-            list1.push(a.parse_wrapped()?);
-            list2.push(b.parse_wrapped()?);
+        let mut scanner = ByteScanner::new(input);
+        let mut line_number = 0;
+        while let Some(line) = scanner.line() {
+            line_number += 1;
+            let mut numbers = ByteScanner::new(line);
+            let (Some(a), Some(b), None) = (
+                numbers.next_uint(),
+                numbers.next_uint(),
+                numbers.next_uint::<u32>(),
+            ) else {
+                bail!("line {line_number} should have exactly two numbers");
+            };
+            list1.push(a.with_context(|| format!("on line {line_number}"))?);
+            list2.push(b.with_context(|| format!("on line {line_number}"))?);
         }
         list1.sort_unstable();
         list2.sort_unstable();
@@ -64,7 +70,7 @@ mod tests {
 
     #[test]
     fn sample_part1() -> Result<()> {
-        let mut problem = Problem::from_str(SAMPLE)?;
+        let mut problem = Problem::parse_input(SAMPLE.as_bytes())?;
 
         let result = problem.part1()?;
 
@@ -75,7 +81,7 @@ mod tests {
 
     #[test]
     fn sample_part2() -> Result<()> {
-        let problem = Problem::from_str(SAMPLE)?;
+        let problem = Problem::parse_input(SAMPLE.as_bytes())?;
 
         let result = problem.part2()?;
 
@@ -84,6 +90,13 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn lines_need_two_numbers() {
+        assert!(Problem::parse_input(b"3   4\n4\n2   5\n").is_err());
+        assert!(Problem::parse_input(b"3   4\n4   3   2\n").is_err());
+        assert!(Problem::parse_input(b"3   4\r\n4   3\r\n").is_ok());
+    }
+
     const SAMPLE: &str = "\
 3   4
 4   3
@@ -22,25 +22,8 @@ impl FromStr for Machine {
     type Err = Error;
 
     fn from_str(contents: &str) -> Result<Self> {
-        let lines: Vec<&str> = contents.lines().collect();
-        if lines.len() != 3 {
-            bail!("invalid machine string");
-        }
-
-        let (_, a) = lines[0].split_once(": ").ok_or_invalid()?;
-        let (a_x, a_y) = a.split_once(", ").ok_or_invalid()?;
-        let a_x = a_x[2..].parse_wrapped()?;
-        let a_y = a_y[2..].parse_wrapped()?;
-
-        let (_, b) = lines[1].split_once(": ").ok_or_invalid()?;
-        let (b_x, b_y) = b.split_once(", ").ok_or_invalid()?;
-        let b_x = b_x[2..].parse_wrapped()?;
-        let b_y = b_y[2..].parse_wrapped()?;
-
-        let (_, prize) = lines[2].split_once(": ").ok_or_invalid()?;
-        let (prize_x, prize_y) = prize.split_once(", ").ok_or_invalid()?;
-        let prize_x = prize_x[2..].parse_wrapped()?;
-        let prize_y = prize_y[2..].parse_wrapped()?;
+        // Button A: X+94, Y+34 / Button B: X+22, Y+67 / Prize: X=8400, Y=5400
+        let [a_x, a_y, b_x, b_y, prize_x, prize_y] = ints_n(contents)?;
 
         Ok(Machine {
             a: ipt(a_x, a_y),
@@ -79,34 +62,60 @@ impl Machine {
         cheapest
     }
 
-    fn cheapest_win_part_2(&self) -> Option<i64> {
-        let a_x = self.a.x as i64;
-        let a_y = self.a.y as i64;
-
-        let b_x = self.b.x as i64;
-        let b_y = self.b.y as i64;
-
-        let p_x = self.prize.x as i64 + 10000000000000;
-        let p_y = self.prize.y as i64 + 10000000000000;
+    fn cheapest_win_part_2(&self) -> Result<Option<i64>> {
+        let offset = 10000000000000;
 
         // we have a system of equations with 2 vars = the number of a presses (a) and b presses
         // (b)
         //
         // prize_x = a(a_x) + b(b_x)
         // prize_y = a(a_y) + b(b_y)
+        let buttons = vec![
+            vec![i64::from(self.a.x), i64::from(self.b.x)],
+            vec![i64::from(self.a.y), i64::from(self.b.y)],
+        ];
+        let prize = [
+            i64::from(self.prize.x) + offset,
+            i64::from(self.prize.y) + offset,
+        ];
+
+        let presses = match solve_integer_system(&buttons, &prize)? {
+            LinearSolution::Unique(presses) => presses,
+            LinearSolution::NoSolution => return Ok(None),
+            // both buttons and the prize are on the same line, so only x matters
+            LinearSolution::InfinitelyMany => {
+                return cheapest_on_line(buttons[0][0], buttons[0][1], prize[0]);
+            }
+        };
+        // this machine only has a win condition if the solutions are whole numbers of presses
+        Ok(match (presses[0].to_integer(), presses[1].to_integer()) {
+            (Some(a), Some(b)) if a >= 0 && b >= 0 => Some(a * 3 + b),
+            _ => None,
+        })
+    }
+}
 
-        // solving the equations yields
-
-        let a = (-b_x * p_y + b_y * p_x) / (a_x * b_y - a_y * b_x);
-        let b = (a_x * p_y - a_y * p_x) / (a_x * b_y - a_y * b_x);
-
-        // check if the solutions were integers - if so, this machine has a win condition
-        if a_x * a + b_x * b == p_x && a_y * a + b_y * b == p_y {
-            Some(a * 3 + b)
-        } else {
-            None
-        }
+// the cheapest a * 3 + b where a_step * a + b_step * b = target, with no negative presses
+fn cheapest_on_line(a_step: i64, b_step: i64, target: i64) -> Result<Option<i64>> {
+    if a_step <= 0 || b_step <= 0 {
+        bail!("buttons should both move forwards along x");
+    }
+    let (g, x, y) = extended_gcd(a_step, b_step);
+    if target % g != 0 {
+        return Ok(None);
+    }
+    // every solution is (a0 + k * a_shift, b0 - k * b_shift) for some k, and keeping both press
+    // counts non-negative limits k to k_min..=k_max
+    let (a0, b0) = (x * (target / g), y * (target / g));
+    let (a_shift, b_shift) = (b_step / g, a_step / g);
+    let k_min = -a0.div_euclid(a_shift);
+    let k_max = b0.div_euclid(b_shift);
+    if k_min > k_max {
+        return Ok(None);
     }
+    // each step of k changes the cost by the same amount, so the cheapest is at one end
+    let k = if 3 * a_shift > b_shift { k_min } else { k_max };
+    Ok(Some((a0 + k * a_shift) * 3 + (b0 - k * b_shift)))
 }
 
 impl Solution for Problem {
@@ -122,11 +131,10 @@ impl Solution for Problem {
     }
 
     fn part2(&self) -> Result<Self::Part2> {
-        Ok(self
-            .machines
+        self.machines
             .iter()
-            .map(|m| m.cheapest_win_part_2().unwrap_or_default())
-            .sum())
+            .map(|m| Ok(m.cheapest_win_part_2()?.unwrap_or_default()))
+            .sum()
     }
 }
 
@@ -145,6 +153,35 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn sample_part2() -> Result<()> {
+        let problem = Problem::from_str(SAMPLE)?;
+
+        let result = problem.part2()?;
+
+        assert_eq!(875318608908, result);
+
+        Ok(())
+    }
+
+    #[test]
+    fn collinear_buttons() -> Result<()> {
+        // A moves 4 for 3 tokens, B moves 1 for 1, so as many A presses as fit
+        assert_eq!(Some(8), cheapest_on_line(4, 1, 10)?);
+        assert_eq!(None, cheapest_on_line(4, 6, 9)?);
+        // 4 * -1 + 6 * 1 = 2, but not without negative presses
+        assert_eq!(None, cheapest_on_line(4, 6, 2)?);
+
+        let machine = Machine {
+            a: ipt(2, 2),
+            b: ipt(1, 1),
+            prize: ipt(0, 0),
+        };
+        assert_eq!(Some(10000000000000), machine.cheapest_win_part_2()?);
+
+        Ok(())
+    }
+
     const SAMPLE: &str = "\
 Button A: X+94, Y+34
 Button B: X+22, Y+67
@@ -1,11 +1,39 @@
 use std::{
-    cmp::{Eq, Ord, Ordering, PartialOrd},
+    cmp::{Eq, Ord, Ordering, PartialOrd, Reverse},
     collections::BinaryHeap,
+    hash::Hash,
     ops::Add,
 };
 
+use anyhow::*;
 use fnv::FnvHashMap;
 
+// Which direction a search or DP is optimizing in
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Objective {
+    Minimize,
+    Maximize,
+}
+
+impl Objective {
+    pub fn is_better<T: Ord>(self, candidate: &T, incumbent: &T) -> bool {
+        match self {
+            Objective::Minimize => candidate < incumbent,
+            Objective::Maximize => candidate > incumbent,
+        }
+    }
+
+    pub fn best<T: Ord>(self, a: T, b: T) -> T {
+        if self.is_better(&b, &a) {
+            b
+        } else {
+            a
+        }
+    }
+}
+
+// State for dijkstra and a_star. Higher scores are always better, so minimization problems
+// should use Reverse<T> as their score, and maximization problems the plain value
 pub trait OptimizationState {
     type CacheKey: Eq + std::hash::Hash;
     type Score: Eq + Ord;
@@ -31,7 +59,7 @@ where
     TState: OptimizationState,
 {
     fn cmp(&self, other: &Self) -> Ordering {
-        // comparing in reverse to minimize instead of maximize
+        // BinaryHeap pops the max, so the best score comes out first
         self.0.score().cmp(&other.0.score())
     }
 }
@@ -94,55 +122,397 @@ where
     None
 }
 
-pub trait AStarState {
-    type CacheKey: Eq + std::hash::Hash;
-    type Score: Eq + Ord;
+// Same as dijkstra, but also returns the cache keys visited from start to the final state
+// (inclusive). Predecessors are tracked by cache key, so states don't need to carry their path
+pub fn dijkstra_with_path<TState, FNext, TI, FFinal>(
+    start_state: TState,
+    next: FNext,
+    final_predicate: FFinal,
+) -> Option<(TState, Vec<TState::CacheKey>)>
+where
+    TState: OptimizationState,
+    TState::CacheKey: Clone,
+    FNext: Fn(&TState) -> TI,
+    TI: IntoIterator<Item = TState>,
+    FFinal: Fn(&TState) -> bool,
+{
+    let mut cache: FnvHashMap<TState::CacheKey, TState::Score> = FnvHashMap::default();
+    let mut predecessors: FnvHashMap<TState::CacheKey, TState::CacheKey> = FnvHashMap::default();
+    let mut heap: BinaryHeap<OptimizationStateWrapper<TState>> = BinaryHeap::new();
+    cache.insert(start_state.cache_key(), start_state.score());
+    heap.push(OptimizationStateWrapper(start_state));
 
-    fn cache_key(&self) -> Self::CacheKey;
-    fn score(&self) -> Self::Score;
+    while let Some(OptimizationStateWrapper(state)) = heap.pop() {
+        let state_key = state.cache_key();
+        if final_predicate(&state) {
+            let mut path = vec![state_key];
+            while let Some(previous) = predecessors.get(&path[path.len() - 1]) {
+                path.push(previous.clone());
+            }
+            path.reverse();
+            return Some((state, path));
+        }
+
+        match cache.get(&state_key) {
+            Some(prev_score) if state.score() < *prev_score => {
+                continue;
+            }
+            _ => (),
+        }
+
+        for next in next(&state) {
+            let key = next.cache_key();
+            let score = next.score();
+            match cache.get(&key) {
+                Some(prev_score) if score <= *prev_score => (),
+                _ => {
+                    cache.insert(key.clone(), score);
+                    predecessors.insert(key, state_key.clone());
+                    heap.push(OptimizationStateWrapper(next));
+                }
+            }
+        }
+    }
+
+    None
 }
 
+// Same as dijkstra, but returns every final state tied for the best score (one per cache key)
+pub fn dijkstra_all_goals<TState, FNext, TI, FFinal>(
+    start_state: TState,
+    next: FNext,
+    final_predicate: FFinal,
+) -> Vec<TState>
+where
+    TState: OptimizationState,
+    FNext: Fn(&TState) -> TI,
+    TI: IntoIterator<Item = TState>,
+    FFinal: Fn(&TState) -> bool,
+{
+    let mut cache: FnvHashMap<TState::CacheKey, TState::Score> = FnvHashMap::default();
+    let mut heap: BinaryHeap<OptimizationStateWrapper<TState>> = BinaryHeap::new();
+    cache.insert(start_state.cache_key(), start_state.score());
+    heap.push(OptimizationStateWrapper(start_state));
+
+    let mut goals: Vec<TState> = vec![];
+
+    while let Some(OptimizationStateWrapper(state)) = heap.pop() {
+        if let Some(best) = goals.first() {
+            if state.score() < best.score() {
+                break;
+            }
+        }
+
+        match cache.get(&state.cache_key()) {
+            Some(prev_score) if state.score() < *prev_score => {
+                continue;
+            }
+            _ => (),
+        }
+
+        // goals are still expanded, since another goal could be reachable at no extra cost
+        for next in next(&state) {
+            let key = next.cache_key();
+            let score = next.score();
+            match cache.get(&key) {
+                Some(prev_score) if score <= *prev_score => (),
+                _ => {
+                    cache.insert(key, score);
+                    heap.push(OptimizationStateWrapper(next));
+                }
+            }
+        }
+
+        if final_predicate(&state) {
+            goals.push(state);
+        }
+    }
+
+    goals
+}
+
+// Runs dijkstra until every reachable state is settled, returning the best score for each
+// cache key (including the start)
+pub fn dijkstra_distances<TState, FNext, TI>(
+    start_state: TState,
+    next: FNext,
+) -> FnvHashMap<TState::CacheKey, TState::Score>
+where
+    TState: OptimizationState,
+    FNext: Fn(&TState) -> TI,
+    TI: IntoIterator<Item = TState>,
+{
+    let mut cache: FnvHashMap<TState::CacheKey, TState::Score> = FnvHashMap::default();
+    let mut heap: BinaryHeap<OptimizationStateWrapper<TState>> = BinaryHeap::new();
+    cache.insert(start_state.cache_key(), start_state.score());
+    heap.push(OptimizationStateWrapper(start_state));
+
+    while let Some(OptimizationStateWrapper(state)) = heap.pop() {
+        match cache.get(&state.cache_key()) {
+            Some(prev_score) if state.score() < *prev_score => {
+                continue;
+            }
+            _ => (),
+        }
+
+        for next in next(&state) {
+            let key = next.cache_key();
+            let score = next.score();
+            match cache.get(&key) {
+                Some(prev_score) if score <= *prev_score => (),
+                _ => {
+                    cache.insert(key, score);
+                    heap.push(OptimizationStateWrapper(next));
+                }
+            }
+        }
+    }
+
+    cache
+}
+
+// Bidirectional Dijkstra, for when both endpoints are known. Because the two searches are joined
+// by adding their costs, this works on nodes and edge costs rather than OptimizationState.
+// forward returns (neighbor, cost) for edges leaving a node, backward returns the same for edges
+// arriving at a node (for undirected graphs these are the same function).
+//
+// Returns the total cost and the nodes from start to goal (inclusive)
+pub fn bidirectional_dijkstra<K, C, FForward, TIF, FBackward, TIB>(
+    start: K,
+    goal: K,
+    forward: FForward,
+    backward: FBackward,
+) -> Option<(C, Vec<K>)>
+where
+    K: Eq + Hash + Clone,
+    C: Copy + Default + Ord + Add<Output = C>,
+    FForward: Fn(&K) -> TIF,
+    TIF: IntoIterator<Item = (K, C)>,
+    FBackward: Fn(&K) -> TIB,
+    TIB: IntoIterator<Item = (K, C)>,
+{
+    if start == goal {
+        return Some((C::default(), vec![start]));
+    }
+
+    let mut searches: [BidirectionalSearch<K, C>; 2] = [
+        BidirectionalSearch::new(start),
+        BidirectionalSearch::new(goal),
+    ];
+    let mut best: Option<(C, K)> = None;
+
+    while let (Some(forward_cost), Some(backward_cost)) =
+        (searches[0].peek_cost(), searches[1].peek_cost())
+    {
+        if let Some((best_cost, _)) = &best {
+            if forward_cost + backward_cost >= *best_cost {
+                break;
+            }
+        }
+
+        // expand whichever frontier is cheaper
+        let side = if forward_cost <= backward_cost { 0 } else { 1 };
+        let Some(Reverse(BidirectionalEntry { cost, node })) = searches[side].frontier.pop() else {
+            break;
+        };
+        if searches[side]
+            .distances
+            .get(&node)
+            .is_some_and(|&d| cost > d)
+        {
+            continue;
+        }
+
+        let neighbors: Vec<(K, C)> = if side == 0 {
+            forward(&node).into_iter().collect()
+        } else {
+            backward(&node).into_iter().collect()
+        };
+        for (neighbor, step) in neighbors {
+            let neighbor_cost = cost + step;
+            if !searches[side].relax(&node, &neighbor, neighbor_cost) {
+                continue;
+            }
+            if let Some(&other_cost) = searches[1 - side].distances.get(&neighbor) {
+                let total = neighbor_cost + other_cost;
+                if best
+                    .as_ref()
+                    .is_none_or(|(best_cost, _)| total < *best_cost)
+                {
+                    best = Some((total, neighbor));
+                }
+            }
+        }
+    }
+
+    let (cost, meeting) = best?;
+    let mut path = searches[0].path_to(&meeting);
+    path.reverse();
+    path.extend(searches[1].path_to(&meeting).into_iter().skip(1));
+    Some((cost, path))
+}
+
+struct BidirectionalEntry<K, C> {
+    cost: C,
+    node: K,
+}
+
+impl<K, C: Ord> PartialEq for BidirectionalEntry<K, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cost == other.cost
+    }
+}
+impl<K, C: Ord> Eq for BidirectionalEntry<K, C> {}
+
+impl<K, C: Ord> Ord for BidirectionalEntry<K, C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.cost.cmp(&other.cost)
+    }
+}
+
+impl<K, C: Ord> PartialOrd for BidirectionalEntry<K, C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+struct BidirectionalSearch<K, C> {
+    distances: FnvHashMap<K, C>,
+    predecessors: FnvHashMap<K, K>,
+    frontier: BinaryHeap<Reverse<BidirectionalEntry<K, C>>>,
+}
+
+impl<K, C> BidirectionalSearch<K, C>
+where
+    K: Eq + Hash + Clone,
+    C: Copy + Default + Ord,
+{
+    fn new(origin: K) -> Self {
+        let mut distances = FnvHashMap::default();
+        distances.insert(origin.clone(), C::default());
+        let mut frontier = BinaryHeap::new();
+        frontier.push(Reverse(BidirectionalEntry {
+            cost: C::default(),
+            node: origin,
+        }));
+        Self {
+            distances,
+            predecessors: FnvHashMap::default(),
+            frontier,
+        }
+    }
+
+    fn peek_cost(&self) -> Option<C> {
+        self.frontier.peek().map(|Reverse(entry)| entry.cost)
+    }
+
+    // returns true if this is the best way found to reach node so far
+    fn relax(&mut self, from: &K, node: &K, cost: C) -> bool {
+        if self.distances.get(node).is_some_and(|&prev| cost >= prev) {
+            return false;
+        }
+        self.distances.insert(node.clone(), cost);
+        self.predecessors.insert(node.clone(), from.clone());
+        self.frontier.push(Reverse(BidirectionalEntry {
+            cost,
+            node: node.clone(),
+        }));
+        true
+    }
+
+    // walks back to this search's origin, starting with node
+    fn path_to(&self, node: &K) -> Vec<K> {
+        let mut path = vec![node.clone()];
+        while let Some(previous) = self.predecessors.get(&path[path.len() - 1]) {
+            path.push(previous.clone());
+        }
+        path
+    }
+}
+
+// Scores that expose the underlying cost, so a_star can add heuristic estimates to them
+pub trait SearchScore: Ord {
+    type Cost: Copy + Ord + Add<Output = Self::Cost>;
+
+    const OBJECTIVE: Objective;
+
+    fn cost(&self) -> Self::Cost;
+}
+
+impl<T> SearchScore for Reverse<T>
+where
+    T: Copy + Ord + Add<Output = T>,
+{
+    type Cost = T;
+
+    const OBJECTIVE: Objective = Objective::Minimize;
+
+    fn cost(&self) -> T {
+        self.0
+    }
+}
+
+macro_rules! maximized_search_score {
+    ($($t:ty),*) => {
+        $(
+            impl SearchScore for $t {
+                type Cost = $t;
+
+                const OBJECTIVE: Objective = Objective::Maximize;
+
+                fn cost(&self) -> $t {
+                    *self
+                }
+            }
+        )*
+    };
+}
+
+maximized_search_score!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
 struct AStarStateWrapper<TState>
 where
     TState: OptimizationState,
+    TState::Score: SearchScore,
 {
     state: TState,
-    heuristic: <TState as OptimizationState>::Score,
+    estimate: <TState::Score as SearchScore>::Cost,
 }
 
 impl<TState> PartialEq for AStarStateWrapper<TState>
 where
     TState: OptimizationState,
-    <TState as OptimizationState>::Score: Copy + Add<Output = <TState as OptimizationState>::Score>,
+    TState::Score: SearchScore,
 {
     fn eq(&self, other: &Self) -> bool {
-        self.state.score() + self.heuristic == other.state.score() + other.heuristic
+        self.estimate == other.estimate
     }
 }
 impl<TState> Eq for AStarStateWrapper<TState>
 where
     TState: OptimizationState,
-    <TState as OptimizationState>::Score: Copy + Add<Output = <TState as OptimizationState>::Score>,
+    TState::Score: SearchScore,
 {
 }
 
 impl<TState> Ord for AStarStateWrapper<TState>
 where
     TState: OptimizationState,
-    <TState as OptimizationState>::Score: Copy + Add<Output = <TState as OptimizationState>::Score>,
+    TState::Score: SearchScore,
 {
     fn cmp(&self, other: &Self) -> Ordering {
-        // comparing in reverse to minimize instead of maximize
-        let my_score = self.state.score() + self.heuristic;
-        let other_score = other.state.score() + other.heuristic;
-        other_score.cmp(&my_score)
+        // BinaryHeap pops the max, so flip the comparison when minimizing
+        match <TState::Score as SearchScore>::OBJECTIVE {
+            Objective::Minimize => other.estimate.cmp(&self.estimate),
+            Objective::Maximize => self.estimate.cmp(&other.estimate),
+        }
     }
 }
 
 impl<TState> PartialOrd for AStarStateWrapper<TState>
 where
     TState: OptimizationState,
-    <TState as OptimizationState>::Score: Copy + Add<Output = <TState as OptimizationState>::Score>,
+    TState::Score: SearchScore,
 {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         Some(self.cmp(other))
@@ -152,8 +522,9 @@ where
 // usually a_star isn't worth it on these problems, the overhead from calling the heuristic
 // function almost always outweighs choosing the correct next node
 //
-// NOTE: OptimizationState impl must not use Reverse because heuristics are added to it, but should
-// still consider lower values as better
+// Takes the same states as dijkstra. h estimates the remaining cost from a state to the goal, and
+// must be admissible for the result to be optimal: never more than the true remaining cost when
+// minimizing (Reverse scores), and never less when maximizing
 pub fn a_star<TState, FNext, TI, FHeuristic, FFinal>(
     start_state: TState,
     next: FNext,
@@ -162,33 +533,28 @@ pub fn a_star<TState, FNext, TI, FHeuristic, FFinal>(
 ) -> Option<TState>
 where
     TState: OptimizationState,
+    TState::Score: SearchScore,
     FNext: Fn(&TState) -> TI,
     TI: IntoIterator<Item = TState>,
-    FHeuristic: Fn(&TState) -> <TState as OptimizationState>::Score,
+    FHeuristic: Fn(&TState) -> <TState::Score as SearchScore>::Cost,
     FFinal: Fn(&TState) -> bool,
-    <TState as OptimizationState>::Score:
-        Default + Copy + Add<Output = <TState as OptimizationState>::Score>,
 {
     let mut cache: FnvHashMap<TState::CacheKey, TState::Score> = FnvHashMap::default();
     let mut heap: BinaryHeap<AStarStateWrapper<TState>> = BinaryHeap::new();
+    let estimate = start_state.score().cost() + h(&start_state);
+    cache.insert(start_state.cache_key(), start_state.score());
     heap.push(AStarStateWrapper {
         state: start_state,
-        heuristic: Default::default(),
+        estimate,
     });
 
-    while let Some(AStarStateWrapper {
-        state,
-        heuristic: _,
-    }) = heap.pop()
-    {
+    while let Some(AStarStateWrapper { state, estimate: _ }) = heap.pop() {
         if final_predicate(&state) {
             return Some(state);
         }
 
         match cache.get(&state.cache_key()) {
-            // note the comparison operators are swapped here, because score for a_star must not be
-            // reversed like dijkstra
-            Some(prev_score) if state.score() > *prev_score => {
+            Some(prev_score) if state.score() < *prev_score => {
                 continue;
             }
             _ => (),
@@ -198,15 +564,13 @@ where
             let key = next.cache_key();
             let score = next.score();
             match cache.get(&key) {
-                // note the comparison operators are swapped here, because score for a_star must not be
-                // reversed like dijkstra
-                Some(prev_score) if score >= *prev_score => (),
+                Some(prev_score) if score <= *prev_score => (),
                 _ => {
+                    let estimate = score.cost() + h(&next);
                     cache.insert(key, score);
-                    let heuristic = h(&next);
                     heap.push(AStarStateWrapper {
                         state: next,
-                        heuristic,
+                        estimate,
                     });
                 }
             }
@@ -215,3 +579,513 @@ where
 
     None
 }
+
+// Counters for the exhaustive searches below, handy for checking how well pruning works
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct SearchStats {
+    pub expanded: usize,
+    pub pruned: usize,
+    pub cache_hits: usize,
+}
+
+// Depth-first branch and bound over the same states as dijkstra and a_star. Useful when scores
+// don't grow monotonically along a path (so dijkstra's first answer isn't the best one), or when
+// the search space is too big to run to completion without pruning.
+//
+// - every state where final_predicate is true is a candidate answer, but is still expanded, so
+//   "stop anywhere" problems just return true for everything
+// - bound estimates the remaining cost from a state, like a_star's heuristic, and must be
+//   optimistic (lower when minimizing, higher when maximizing) for the answer to be optimal
+// - a state is dropped when another state with the same cache key already reached a score at
+//   least as good, so the cache key must include everything that affects future moves
+//
+// Successors are explored in the order next returns them, so returning promising moves first
+// finds a good incumbent sooner and prunes more
+pub fn branch_and_bound<TState, FNext, TI, FBound, FFinal>(
+    start_state: TState,
+    next: FNext,
+    bound: FBound,
+    final_predicate: FFinal,
+) -> (Option<TState>, SearchStats)
+where
+    TState: OptimizationState,
+    TState::Score: SearchScore,
+    FNext: Fn(&TState) -> TI,
+    TI: IntoIterator<Item = TState>,
+    FBound: Fn(&TState) -> <TState::Score as SearchScore>::Cost,
+    FFinal: Fn(&TState) -> bool,
+{
+    let objective = <TState::Score as SearchScore>::OBJECTIVE;
+    let mut stats = SearchStats::default();
+    let mut best: Option<TState> = None;
+    let mut seen: FnvHashMap<TState::CacheKey, TState::Score> = FnvHashMap::default();
+    let mut stack = vec![start_state];
+
+    while let Some(state) = stack.pop() {
+        let score = state.score();
+        if let Some(incumbent) = &best {
+            let optimistic = score.cost() + bound(&state);
+            if !objective.is_better(&optimistic, &incumbent.score().cost()) {
+                stats.pruned += 1;
+                continue;
+            }
+        }
+
+        let key = state.cache_key();
+        match seen.get(&key) {
+            Some(prev_score) if score <= *prev_score => {
+                stats.cache_hits += 1;
+                continue;
+            }
+            _ => {
+                seen.insert(key, score);
+            }
+        }
+
+        stats.expanded += 1;
+        let successors: Vec<TState> = next(&state).into_iter().collect();
+
+        if final_predicate(&state) && best.as_ref().is_none_or(|b| state.score() > b.score()) {
+            best = Some(state);
+        }
+
+        // reversed so the first successor is explored first
+        stack.extend(successors.into_iter().rev());
+    }
+
+    (best, stats)
+}
+
+// Top-down dynamic programming: the value of a state is its leaf value if it has one, otherwise
+// the best of (step value + value of successor) over its successors. States that are neither
+// leaves nor have successors are dead ends and have no value. Results are memoized by cache_key,
+// so it must include everything that affects the value of a state
+//
+// The states must form a DAG. Reaching a state again while it's still being expanded is an error,
+// since the values around a cycle depend on each other and can't be memoized
+pub fn memoized_dfs<TState, K, V, FKey, FNext, TI, FLeaf>(
+    start_state: TState,
+    cache_key: FKey,
+    next: FNext,
+    leaf_value: FLeaf,
+    objective: Objective,
+) -> Result<(Option<V>, SearchStats)>
+where
+    K: Eq + Hash,
+    V: Copy + Ord + Add<Output = V>,
+    FKey: Fn(&TState) -> K,
+    FNext: Fn(&TState) -> TI,
+    TI: IntoIterator<Item = (TState, V)>,
+    FLeaf: Fn(&TState) -> Option<V>,
+{
+    let search = MemoizedDfs {
+        cache_key,
+        next,
+        leaf_value,
+        objective,
+    };
+    let mut cache = FnvHashMap::default();
+    let mut stats = SearchStats::default();
+    let value = search.value(start_state, &mut cache, &mut stats)?;
+    Ok((value, stats))
+}
+
+struct MemoizedDfs<FKey, FNext, FLeaf> {
+    cache_key: FKey,
+    next: FNext,
+    leaf_value: FLeaf,
+    objective: Objective,
+}
+
+enum Memo<V> {
+    InProgress,
+    Done(Option<V>),
+}
+
+impl<FKey, FNext, FLeaf> MemoizedDfs<FKey, FNext, FLeaf> {
+    fn value<TState, K, V, TI>(
+        &self,
+        state: TState,
+        cache: &mut FnvHashMap<K, Memo<V>>,
+        stats: &mut SearchStats,
+    ) -> Result<Option<V>>
+    where
+        K: Eq + Hash,
+        V: Copy + Ord + Add<Output = V>,
+        FKey: Fn(&TState) -> K,
+        FNext: Fn(&TState) -> TI,
+        TI: IntoIterator<Item = (TState, V)>,
+        FLeaf: Fn(&TState) -> Option<V>,
+    {
+        let key = (self.cache_key)(&state);
+        match cache.get(&key) {
+            Some(Memo::Done(value)) => {
+                stats.cache_hits += 1;
+                return Ok(*value);
+            }
+            Some(Memo::InProgress) => bail!("memoized_dfs reached a cycle, states must form a DAG"),
+            None => (),
+        }
+
+        let value = match (self.leaf_value)(&state) {
+            Some(value) => Some(value),
+            None => {
+                cache.insert(key, Memo::InProgress);
+                stats.expanded += 1;
+                let mut best = None;
+                for (successor, step) in (self.next)(&state) {
+                    if let Some(value) = self.value(successor, cache, stats)? {
+                        let value = step + value;
+                        best = Some(best.map_or(value, |b| self.objective.best(b, value)));
+                    }
+                }
+                best
+            }
+        };
+
+        cache.insert((self.cache_key)(&state), Memo::Done(value));
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 0 --1-- 1 --1-- 3
+    //  \             /
+    //   4---- 2 ---1
+    //          \--5-- 4
+    const EDGES: [(usize, usize, u32); 5] = [(0, 1, 1), (1, 3, 1), (0, 2, 4), (2, 3, 1), (2, 4, 5)];
+
+    fn neighbors(node: usize) -> Vec<(usize, u32)> {
+        EDGES
+            .iter()
+            .filter_map(|&(a, b, cost)| match node {
+                n if n == a => Some((b, cost)),
+                n if n == b => Some((a, cost)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[derive(Debug, PartialEq, Eq)]
+    struct NodeState {
+        node: usize,
+        cost: u32,
+    }
+
+    impl OptimizationState for NodeState {
+        type CacheKey = usize;
+        type Score = Reverse<u32>;
+
+        fn cache_key(&self) -> usize {
+            self.node
+        }
+
+        fn score(&self) -> Reverse<u32> {
+            Reverse(self.cost)
+        }
+    }
+
+    fn next_states(state: &NodeState) -> Vec<NodeState> {
+        neighbors(state.node)
+            .into_iter()
+            .map(|(node, cost)| NodeState {
+                node,
+                cost: state.cost + cost,
+            })
+            .collect()
+    }
+
+    fn start() -> NodeState {
+        NodeState { node: 0, cost: 0 }
+    }
+
+    #[test]
+    fn dijkstra_with_path_reconstructs_route() {
+        let (state, path) = dijkstra_with_path(start(), next_states, |s| s.node == 4).unwrap();
+        assert_eq!(8, state.cost);
+        assert_eq!(vec![0, 1, 3, 2, 4], path);
+
+        let (state, path) = dijkstra_with_path(start(), next_states, |s| s.node == 0).unwrap();
+        assert_eq!(0, state.cost);
+        assert_eq!(vec![0], path);
+    }
+
+    #[test]
+    fn dijkstra_all_goals_returns_ties() {
+        // nodes 1 and 3 are 1 and 2 away, node 2 is 3 away via 1 -> 3 -> 2
+        let goals = dijkstra_all_goals(start(), next_states, |s| s.node >= 2);
+        assert_eq!(vec![NodeState { node: 3, cost: 2 }], goals);
+
+        let goals = dijkstra_all_goals(start(), next_states, |s| s.cost >= 1);
+        assert_eq!(vec![NodeState { node: 1, cost: 1 }], goals);
+
+        let tied_edges = |state: &NodeState| {
+            [(1, 2), (2, 2), (3, 3)]
+                .into_iter()
+                .filter(|_| state.node == 0)
+                .map(|(node, cost)| NodeState { node, cost })
+                .collect::<Vec<_>>()
+        };
+        let mut goals: Vec<usize> = dijkstra_all_goals(start(), tied_edges, |s| s.node != 0)
+            .into_iter()
+            .map(|s| s.node)
+            .collect();
+        goals.sort();
+        assert_eq!(vec![1, 2], goals);
+    }
+
+    #[test]
+    fn dijkstra_distances_settles_everything() {
+        let distances = dijkstra_distances(start(), next_states);
+        let mut distances: Vec<(usize, u32)> = distances
+            .into_iter()
+            .map(|(node, Reverse(cost))| (node, cost))
+            .collect();
+        distances.sort();
+        assert_eq!(vec![(0, 0), (1, 1), (2, 3), (3, 2), (4, 8)], distances);
+    }
+
+    #[test]
+    fn bidirectional_matches_dijkstra() {
+        for goal in 0..5 {
+            let expected = dijkstra_with_path(start(), next_states, |s| s.node == goal).unwrap();
+            let (cost, path) =
+                bidirectional_dijkstra(0, goal, |&n| neighbors(n), |&n| neighbors(n)).unwrap();
+            assert_eq!(expected.0.cost, cost);
+            assert_eq!(expected.1, path);
+        }
+
+        // 5 is not connected to anything
+        assert_eq!(
+            None,
+            bidirectional_dijkstra(0, 5, |&n| neighbors(n), |&n| neighbors(n))
+        );
+    }
+
+    #[test]
+    fn a_star_matches_dijkstra_when_minimizing() {
+        // every edge costs at least 1, so 1 is a lower bound from anywhere but the goal
+        for goal in 0..5 {
+            let expected = dijkstra(start(), next_states, |s| s.node == goal).unwrap();
+            let found = a_star(
+                start(),
+                next_states,
+                |s| u32::from(s.node != goal),
+                |s| s.node == goal,
+            )
+            .unwrap();
+            assert_eq!(expected, found);
+        }
+    }
+
+    // maximizing remaining fuel is the same as minimizing cost
+    #[derive(Debug, PartialEq, Eq)]
+    struct FuelState {
+        node: usize,
+        fuel: i32,
+    }
+
+    impl OptimizationState for FuelState {
+        type CacheKey = usize;
+        type Score = i32;
+
+        fn cache_key(&self) -> usize {
+            self.node
+        }
+
+        fn score(&self) -> i32 {
+            self.fuel
+        }
+    }
+
+    #[test]
+    fn a_star_matches_dijkstra_when_maximizing() {
+        let next_fuel = |state: &FuelState| {
+            neighbors(state.node)
+                .into_iter()
+                .map(|(node, cost)| FuelState {
+                    node,
+                    fuel: state.fuel - cost as i32,
+                })
+                .collect::<Vec<_>>()
+        };
+        let start = || FuelState { node: 0, fuel: 20 };
+
+        let expected = dijkstra(start(), next_fuel, |s| s.node == 4).unwrap();
+        assert_eq!(12, expected.fuel);
+
+        // an upper bound on the change in fuel still to come
+        let found = a_star(
+            start(),
+            next_fuel,
+            |s| -i32::from(s.node != 4),
+            |s| s.node == 4,
+        );
+        assert_eq!(Some(expected), found);
+    }
+
+    const ITEMS: [(u32, u32); 6] = [(12, 4), (2, 2), (1, 1), (4, 10), (1, 2), (7, 8)];
+    const CAPACITY: u32 = 15;
+
+    fn knapsack_brute_force() -> u32 {
+        (0..1_usize << ITEMS.len())
+            .filter_map(|mask| {
+                let chosen = ITEMS
+                    .iter()
+                    .enumerate()
+                    .filter(|(ix, _)| mask & (1 << ix) != 0);
+                let weight: u32 = chosen.clone().map(|(_, &(w, _))| w).sum();
+                let value: u32 = chosen.map(|(_, &(_, v))| v).sum();
+                (weight <= CAPACITY).then_some(value)
+            })
+            .max()
+            .unwrap()
+    }
+
+    #[derive(Debug)]
+    struct KnapsackState {
+        item: usize,
+        weight: u32,
+        value: u32,
+    }
+
+    impl OptimizationState for KnapsackState {
+        type CacheKey = (usize, u32);
+        type Score = u32;
+
+        fn cache_key(&self) -> (usize, u32) {
+            (self.item, self.weight)
+        }
+
+        fn score(&self) -> u32 {
+            self.value
+        }
+    }
+
+    fn knapsack_next(state: &KnapsackState) -> Vec<KnapsackState> {
+        let Some(&(weight, value)) = ITEMS.get(state.item) else {
+            return vec![];
+        };
+        let mut next = vec![];
+        if state.weight + weight <= CAPACITY {
+            next.push(KnapsackState {
+                item: state.item + 1,
+                weight: state.weight + weight,
+                value: state.value + value,
+            });
+        }
+        next.push(KnapsackState {
+            item: state.item + 1,
+            ..*state
+        });
+        next
+    }
+
+    #[test]
+    fn branch_and_bound_solves_knapsack() {
+        let start = || KnapsackState {
+            item: 0,
+            weight: 0,
+            value: 0,
+        };
+        let expected = knapsack_brute_force();
+
+        // taking everything that's left is an upper bound
+        let remaining = |s: &KnapsackState| ITEMS[s.item..].iter().map(|&(_, v)| v).sum();
+        let (best, stats) =
+            branch_and_bound(start(), knapsack_next, remaining, |s| s.item == ITEMS.len());
+        assert_eq!(expected, best.unwrap().value);
+        assert!(stats.pruned > 0);
+
+        let (best, unbounded) = branch_and_bound(
+            start(),
+            knapsack_next,
+            |_| u32::MAX / 2,
+            |s| s.item == ITEMS.len(),
+        );
+        assert_eq!(expected, best.unwrap().value);
+        assert_eq!(0, unbounded.pruned);
+        assert!(unbounded.expanded > stats.expanded);
+    }
+
+    #[test]
+    fn branch_and_bound_minimizes_reverse_scores() {
+        // same graph as the dijkstra tests, but searched depth first
+        let (best, _) = branch_and_bound(
+            start(),
+            |s| {
+                next_states(s)
+                    .into_iter()
+                    .filter(|n| n.cost < 20)
+                    .collect::<Vec<_>>()
+            },
+            |_| 0,
+            |s| s.node == 4,
+        );
+        assert_eq!(Some(NodeState { node: 4, cost: 8 }), best);
+    }
+
+    #[test]
+    fn memoized_dfs_matches_brute_force() -> Result<()> {
+        let (best, stats) = memoized_dfs(
+            (0_usize, 0_u32),
+            |&state| state,
+            |&(item, weight)| {
+                let (item_weight, item_value) = ITEMS[item];
+                let mut next = vec![((item + 1, weight), 0)];
+                if weight + item_weight <= CAPACITY {
+                    next.push(((item + 1, weight + item_weight), item_value));
+                }
+                next
+            },
+            |&(item, _)| (item == ITEMS.len()).then_some(0),
+            Objective::Maximize,
+        )?;
+        assert_eq!(Some(knapsack_brute_force()), best);
+        assert!(stats.cache_hits > 0);
+
+        // fewest coins to make 11 from 1, 5 and 7, with no way to make 3 from 4 and 5
+        let coins = |coins: &'static [u32], target: u32| {
+            memoized_dfs(
+                target,
+                |&left| left,
+                |&left| {
+                    coins
+                        .iter()
+                        .filter(move |&&c| c <= left)
+                        .map(move |&c| (left - c, 1))
+                },
+                |&left| (left == 0).then_some(0),
+                Objective::Minimize,
+            )
+            .map(|(best, _)| best)
+        };
+        assert_eq!(Some(3), coins(&[1, 5, 7], 11)?);
+        assert_eq!(None, coins(&[4, 5], 3)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn memoized_dfs_rejects_cycles() {
+        // S -> A -> B -> G costs 3, but B -> A loops back. Cutting the loop at A used to cache a
+        // value for B that skipped its cheap edge to G, giving 11
+        let edges: [&[(usize, u32)]; 4] = [
+            &[(2, 10), (1, 1)],
+            &[(2, 1), (3, 100)],
+            &[(1, 1), (3, 1)],
+            &[],
+        ];
+        let search = memoized_dfs(
+            0,
+            |&node| node,
+            |&node| edges[node].to_vec(),
+            |&node| (node == 3).then_some(0),
+            Objective::Minimize,
+        );
+        assert!(search.is_err());
+    }
+}
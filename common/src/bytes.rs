@@ -0,0 +1,401 @@
+// zero-copy parsing over the raw input bytes, for days where &str parsing shows up in the parse
+// timings. Solutions opt in by implementing ParseInput instead of FromStr:
+//
+//   impl ParseInput for Problem {
+//       fn parse_input(input: &[u8]) -> Result<Self> {
+//           let mut scanner = ByteScanner::new(input);
+//           while let Some(value) = scanner.next_uint::<u32>() { ... }
+//       }
+//   }
+use std::ops::Index;
+use std::str::FromStr;
+
+use anyhow::*;
+
+use crate::{parse_all, Bounds2D, Grid2D, ParseError, Point2D};
+
+// how Solution::go turns input.txt into a solution. Every FromStr type gets this through
+// parse_all, so only byte parsers need to implement it
+pub trait ParseInput: Sized {
+    fn parse_input(input: &[u8]) -> Result<Self>;
+}
+
+impl<T> ParseInput for T
+where
+    T: FromStr,
+    <T as FromStr>::Err: std::fmt::Display + 'static,
+{
+    fn parse_input(input: &[u8]) -> Result<Self> {
+        parse_all(std::str::from_utf8(input)?)
+    }
+}
+
+// a ParseError at input[pos..], falling back to a byte offset when input isn't utf-8
+fn byte_error<T>(input: &[u8], pos: usize, message: impl std::fmt::Display) -> Error {
+    match std::str::from_utf8(input) {
+        std::result::Result::Ok(text) if text.is_char_boundary(pos) => {
+            let mut err = ParseError::new::<T>(&text[pos..], message);
+            err.columns = 0..text[pos..].chars().next().map_or(0, char::len_utf8);
+            Error::from(err.anchor(text, &text[pos..]))
+        }
+        _ => anyhow!("failed to parse `{}` at byte {pos}: {message}", {
+            std::any::type_name::<T>()
+        }),
+    }
+}
+
+// digits as an unsigned number, without any allocation or utf-8 checks
+pub fn parse_uint<T: TryFrom<u64>>(digits: &[u8]) -> Result<T> {
+    if digits.is_empty() {
+        return Err(byte_error::<T>(digits, 0, "expected digits"));
+    }
+    let mut value: u64 = 0;
+    for (ix, &digit) in digits.iter().enumerate() {
+        if !digit.is_ascii_digit() {
+            return Err(byte_error::<T>(digits, ix, "expected a digit"));
+        }
+        value = value
+            .checked_mul(10)
+            .and_then(|value| value.checked_add(u64::from(digit - b'0')))
+            .ok_or_else(|| byte_error::<T>(digits, 0, "number too large"))?;
+    }
+    T::try_from(value).map_err(|_| byte_error::<T>(digits, 0, "number too large"))
+}
+
+// an optionally negative number
+pub fn parse_int<T: TryFrom<i64>>(digits: &[u8]) -> Result<T> {
+    let (negative, magnitude) = match digits.split_first() {
+        Some((b'-', magnitude)) => (true, magnitude),
+        _ => (false, digits),
+    };
+    let magnitude: u64 = parse_uint(magnitude)
+        .map_err(|_| byte_error::<T>(digits, 0, "expected an integer that fits in 64 bits"))?;
+    let value = if negative {
+        0_i64.checked_sub_unsigned(magnitude)
+    } else {
+        i64::try_from(magnitude).ok()
+    };
+    value
+        .and_then(|value| T::try_from(value).ok())
+        .ok_or_else(|| byte_error::<T>(digits, 0, "number out of range"))
+}
+
+// like str::lines, over bytes
+pub fn byte_lines(input: &[u8]) -> impl Iterator<Item = &[u8]> {
+    let empty = input.is_empty();
+    input
+        .strip_suffix(b"\n")
+        .unwrap_or(input)
+        .split(|&b| b == b'\n')
+        .filter(move |_| !empty)
+        .map(|line| line.strip_suffix(b"\r").unwrap_or(line))
+}
+
+pub struct ByteScanner<'a> {
+    input: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> ByteScanner<'a> {
+    pub fn new(input: &'a [u8]) -> Self {
+        ByteScanner { input, pos: 0 }
+    }
+
+    pub fn pos(&self) -> usize {
+        self.pos
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.pos >= self.input.len()
+    }
+
+    pub fn rest(&self) -> &'a [u8] {
+        &self.input[self.pos.min(self.input.len())..]
+    }
+
+    pub fn peek(&self) -> Option<u8> {
+        self.input.get(self.pos).copied()
+    }
+
+    pub fn skip(&mut self, count: usize) {
+        self.pos += count;
+    }
+
+    pub fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(|b| b.is_ascii_whitespace()) {
+            self.pos += 1;
+        }
+    }
+
+    // consumes literal, or errors without moving
+    pub fn expect(&mut self, literal: &[u8]) -> Result<()> {
+        if !self.rest().starts_with(literal) {
+            return Err(
+                self.error::<&[u8]>(format!("expected `{}`", String::from_utf8_lossy(literal)))
+            );
+        }
+        self.pos += literal.len();
+        Ok(())
+    }
+
+    // everything before the next delimiter (or the end), consuming the delimiter
+    pub fn take_until(&mut self, delimiter: u8) -> &'a [u8] {
+        let rest = self.rest();
+        let len = rest
+            .iter()
+            .position(|&b| b == delimiter)
+            .unwrap_or(rest.len());
+        self.pos += (len + 1).min(rest.len());
+        &rest[..len]
+    }
+
+    // the next line, without its line ending
+    pub fn line(&mut self) -> Option<&'a [u8]> {
+        if self.is_done() {
+            return None;
+        }
+        let line = self.take_until(b'\n');
+        Some(line.strip_suffix(b"\r").unwrap_or(line))
+    }
+
+    // an unsigned number starting right here
+    pub fn uint<T: TryFrom<u64>>(&mut self) -> Result<T> {
+        let start = self.pos;
+        let len = self.digits_at(start);
+        self.pos += len;
+        parse_uint(&self.input[start..start + len])
+            .map_err(|_| self.error_at::<T>(start, "expected an unsigned integer"))
+    }
+
+    // an optionally negative number starting right here
+    pub fn int<T: TryFrom<i64>>(&mut self) -> Result<T> {
+        let start = self.pos;
+        let sign = usize::from(self.peek() == Some(b'-'));
+        let len = sign + self.digits_at(start + sign);
+        self.pos += len;
+        parse_int(&self.input[start..start + len])
+            .map_err(|_| self.error_at::<T>(start, "expected an integer"))
+    }
+
+    // skips ahead to the next run of digits and parses it, None when there are no more
+    pub fn next_uint<T: TryFrom<u64>>(&mut self) -> Option<Result<T>> {
+        self.pos += self.rest().iter().position(u8::is_ascii_digit)?;
+        Some(self.uint())
+    }
+
+    // skips ahead to the next integer and parses it. A '-' right before the digits is a sign
+    // unless it joins two words, like "x-1" or "10-20", the same as ints()
+    pub fn next_int<T: TryFrom<i64>>(&mut self) -> Option<Result<T>> {
+        let start = self.pos;
+        let digit = start + self.rest().iter().position(u8::is_ascii_digit)?;
+        self.pos = digit;
+        if digit > start
+            && self.input[digit - 1] == b'-'
+            && (digit == 1 || !self.input[digit - 2].is_ascii_alphanumeric())
+        {
+            self.pos -= 1;
+        }
+        Some(self.int())
+    }
+
+    pub fn error<T>(&self, message: impl std::fmt::Display) -> Error {
+        self.error_at::<T>(self.pos.min(self.input.len()), message)
+    }
+
+    fn error_at<T>(&self, pos: usize, message: impl std::fmt::Display) -> Error {
+        byte_error::<T>(self.input, pos, message)
+    }
+
+    fn digits_at(&self, pos: usize) -> usize {
+        self.input.get(pos..).map_or(0, |rest| {
+            rest.iter().take_while(|b| b.is_ascii_digit()).count()
+        })
+    }
+}
+
+// a borrowed grid of bytes, one row per line. Rows are slices of the input, so nothing is copied
+// until converting to a Grid2D
+pub struct ByteGrid<'a> {
+    data: &'a [u8],
+    // distance between the starts of rows, including the line ending
+    stride: usize,
+    pub bounds: Bounds2D,
+}
+
+impl<'a> ByteGrid<'a> {
+    pub fn new(input: &'a [u8]) -> Result<Self> {
+        let mut end = input.len();
+        while end > 0 && matches!(input[end - 1], b'\n' | b'\r') {
+            end -= 1;
+        }
+        let data = &input[..end];
+
+        let width = data
+            .iter()
+            .position(|&b| b == b'\n' || b == b'\r')
+            .unwrap_or(data.len());
+        let line_ending: &[u8] = if data[width..].starts_with(b"\r\n") {
+            b"\r\n"
+        } else {
+            b"\n"
+        };
+        let stride = width + line_ending.len();
+        let height = data.len().div_ceil(stride);
+
+        let grid = ByteGrid {
+            data,
+            stride,
+            bounds: Bounds2D { width, height },
+        };
+        for y in 0..height {
+            let start = y * stride;
+            let line_end = (start + width).min(data.len());
+            // every row but the last is followed by the same line ending
+            let row_ends = line_end == data.len() || data[line_end..].starts_with(line_ending);
+            if line_end - start != width || !row_ends {
+                return Err(byte_error::<Self>(
+                    input,
+                    start,
+                    format!("expected every row to be {width} wide"),
+                ));
+            }
+        }
+        Ok(grid)
+    }
+
+    pub fn row(&self, y: usize) -> &'a [u8] {
+        &self.data[y * self.stride..y * self.stride + self.bounds.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &'a [u8]> + '_ {
+        (0..self.bounds.height).map(|y| self.row(y))
+    }
+
+    pub fn get(&self, pt: Point2D) -> Option<u8> {
+        (pt.x < self.bounds.width && pt.y < self.bounds.height)
+            .then(|| self.data[pt.y * self.stride + pt.x])
+    }
+
+    // the first location of byte, in reading order. Line endings aren't part of the grid, so they
+    // are never found
+    pub fn find(&self, byte: u8) -> Option<Point2D> {
+        self.rows().enumerate().find_map(|(y, row)| {
+            let x = row.iter().position(|&b| b == byte)?;
+            Some(Point2D { x, y })
+        })
+    }
+
+    pub fn to_grid(&self) -> Grid2D<u8> {
+        self.to_grid_with(|b| b)
+    }
+
+    pub fn to_grid_with<T>(&self, f: impl Fn(u8) -> T) -> Grid2D<T> {
+        Grid2D {
+            data: self
+                .rows()
+                .map(|row| row.iter().map(|&b| f(b)).collect())
+                .collect(),
+            bounds: self.bounds,
+        }
+    }
+}
+
+impl Index<Point2D> for ByteGrid<'_> {
+    type Output = u8;
+
+    fn index(&self, pt: Point2D) -> &u8 {
+        assert!(pt.x < self.bounds.width, "{pt:?} is out of bounds");
+        &self.data[pt.y * self.stride + pt.x]
+    }
+}
+
+impl From<ByteGrid<'_>> for Grid2D<u8> {
+    fn from(grid: ByteGrid) -> Self {
+        grid.to_grid()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pt;
+
+    #[test]
+    fn scans_integers() -> Result<()> {
+        let mut scanner = ByteScanner::new(b"p=0,-4 v=3-x-3 10-20\n");
+        let mut found: Vec<i32> = vec![];
+        while let Some(value) = scanner.next_int() {
+            found.push(value?);
+        }
+        assert_eq!(vec![0, -4, 3, 3, 10, 20], found);
+
+        let mut scanner = ByteScanner::new(b"3   4\r\n40   30");
+        assert_eq!(3_u8, scanner.uint()?);
+        scanner.skip_whitespace();
+        assert_eq!(4_u8, scanner.uint()?);
+        assert_eq!(Some(&b"40   30"[..]), {
+            scanner.line();
+            scanner.line()
+        });
+        assert_eq!(None, scanner.line());
+
+        assert_eq!(
+            -9_223_372_036_854_775_808_i64,
+            parse_int(b"-9223372036854775808")?
+        );
+        assert!(parse_uint::<u8>(b"256").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn scanner_errors_have_location() {
+        let mut scanner = ByteScanner::new(b"1 2\n3 x");
+        scanner.skip(6);
+        let err = scanner.int::<i32>().unwrap_err();
+        let err = err.downcast_ref::<ParseError>().unwrap();
+        assert_eq!(Some(2), err.line);
+        assert_eq!(2..3, err.columns);
+        assert_eq!("i32", err.target);
+
+        let mut scanner = ByteScanner::new(b"move 1");
+        assert!(scanner.expect(b"from").is_err());
+        assert_eq!(0, scanner.pos());
+        assert!(scanner.expect(b"move ").is_ok());
+    }
+
+    #[test]
+    fn byte_lines_match_str_lines() {
+        for text in ["a\nb\r\n\nc\n", "", "\n", "one"] {
+            let expected: Vec<&[u8]> = text.lines().map(str::as_bytes).collect();
+            assert_eq!(expected, byte_lines(text.as_bytes()).collect::<Vec<_>>());
+        }
+    }
+
+    #[test]
+    fn byte_grids() -> Result<()> {
+        for input in ["#.S\n..#\n", "#.S\r\n..#\r\n", "#.S\n..#"] {
+            let grid = ByteGrid::new(input.as_bytes())?;
+            assert_eq!(
+                Bounds2D {
+                    width: 3,
+                    height: 2
+                },
+                grid.bounds
+            );
+            assert_eq!(Some(pt(2, 0)), grid.find(b'S'));
+            assert_eq!(None, grid.find(b'\n'));
+            assert_eq!(None, grid.find(b'\r'));
+            assert_eq!(b'#', grid[pt(2, 1)]);
+            assert_eq!(None, grid.get(pt(3, 0)));
+
+            let grid: Grid2D<u8> = grid.into();
+            assert_eq!(b"..#", &grid.data[1][..]);
+        }
+
+        let err = ByteGrid::new(b"123\n45\n678").err().unwrap();
+        assert_eq!(Some(2), err.downcast_ref::<ParseError>().unwrap().line);
+
+        Ok(())
+    }
+}
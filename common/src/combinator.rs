@@ -0,0 +1,323 @@
+// small parser combinators for nested and recursive formats. A parser is anything implementing
+// Fn(&str) -> Parsed<T>, returning the parsed value and the rest of the input. Recursive formats
+// use a plain fn as the recursive parser:
+//
+//   fn packet(input: &str) -> Parsed<'_, Packet> {
+//       number()
+//           .map(Integer)
+//           .or(delimited(literal("["), separated(packet, literal(",")), literal("]")).map(List))
+//           .parse_next(input)
+//   }
+//
+//   let packet: Packet = parse_with(line, packet)?;
+use std::cmp::Ordering;
+use std::fmt::Display;
+use std::str::FromStr;
+
+use anyhow::{Error, Result};
+
+use crate::{short_type_name, ParseError};
+
+pub type Parsed<'a, T> = std::result::Result<(T, &'a str), Expected<'a>>;
+
+// a failed parse, rest is the input the parser gave up on
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Expected<'a> {
+    pub rest: &'a str,
+    pub what: String,
+}
+
+impl<'a> Expected<'a> {
+    pub fn new(rest: &'a str, what: impl Display) -> Self {
+        Expected {
+            rest,
+            what: what.to_string(),
+        }
+    }
+
+    // keep whichever failure got further into the input
+    fn furthest(self, other: Self) -> Self {
+        match self.rest.len().cmp(&other.rest.len()) {
+            Ordering::Less => self,
+            Ordering::Greater => other,
+            Ordering::Equal => Expected {
+                rest: self.rest,
+                what: format!("{} or {}", self.what, other.what),
+            },
+        }
+    }
+}
+
+pub trait Parser<'a, T> {
+    fn parse_next(&self, input: &'a str) -> Parsed<'a, T>;
+
+    fn map<U, F>(self, f: F) -> impl Parser<'a, U>
+    where
+        Self: Sized,
+        F: Fn(T) -> U,
+    {
+        move |input: &'a str| self.parse_next(input).map(|(value, rest)| (f(value), rest))
+    }
+
+    // this parser followed by next
+    fn then<U, P>(self, next: P) -> impl Parser<'a, (T, U)>
+    where
+        Self: Sized,
+        P: Parser<'a, U>,
+    {
+        move |input: &'a str| {
+            let (first, rest) = self.parse_next(input)?;
+            let (second, rest) = next.parse_next(rest)?;
+            Ok(((first, second), rest))
+        }
+    }
+
+    // this parser, or other when this one fails
+    fn or<P>(self, other: P) -> impl Parser<'a, T>
+    where
+        Self: Sized,
+        P: Parser<'a, T>,
+    {
+        move |input: &'a str| {
+            self.parse_next(input).or_else(|first| {
+                other
+                    .parse_next(input)
+                    .map_err(|second| first.furthest(second))
+            })
+        }
+    }
+}
+
+impl<'a, T, F> Parser<'a, T> for F
+where
+    F: Fn(&'a str) -> Parsed<'a, T>,
+{
+    fn parse_next(&self, input: &'a str) -> Parsed<'a, T> {
+        self(input)
+    }
+}
+
+// runs parser over all of contents, ignoring surrounding whitespace. Failures are reported as a
+// ParseError pointing at the input the parser gave up on
+pub fn parse_with<'a, T>(contents: &'a str, parser: impl Parser<'a, T>) -> Result<T> {
+    let (value, rest) = parser
+        .parse_next(contents.trim())
+        .map_err(|expected| expected_error::<T>(contents, expected))?;
+    if !rest.is_empty() {
+        return Err(expected_error::<T>(
+            contents,
+            Expected::new(rest.trim_start(), "end of input"),
+        ));
+    }
+    Ok(value)
+}
+
+fn expected_error<T>(contents: &str, expected: Expected) -> Error {
+    let mut err = ParseError::new::<T>(expected.rest, format!("expected {}", expected.what));
+    err.columns = 0..err.text.chars().next().map_or(0, char::len_utf8);
+    Error::from(err.anchor(contents, expected.rest))
+}
+
+pub fn literal<'a>(expected: &'static str) -> impl Parser<'a, &'a str> {
+    move |input: &'a str| match input.strip_prefix(expected) {
+        Some(rest) => Ok((&input[..expected.len()], rest)),
+        None => Err(Expected::new(input, format!("`{expected}`"))),
+    }
+}
+
+// zero or more chars matching pred
+pub fn take_while<'a>(pred: impl Fn(char) -> bool) -> impl Parser<'a, &'a str> {
+    move |input: &'a str| {
+        let end = input.find(|c: char| !pred(c)).unwrap_or(input.len());
+        Ok(input.split_at(end))
+    }
+}
+
+// one or more chars matching pred, what describes them for errors
+pub fn take_while1<'a>(
+    what: &'static str,
+    pred: impl Fn(char) -> bool,
+) -> impl Parser<'a, &'a str> {
+    let chars = take_while(pred);
+    move |input: &'a str| match chars.parse_next(input)? {
+        ("", _) => Err(Expected::new(input, what)),
+        parsed => Ok(parsed),
+    }
+}
+
+// letters, digits, and underscores
+pub fn word<'a>() -> impl Parser<'a, &'a str> {
+    take_while1("a word", |c| c.is_alphanumeric() || c == '_')
+}
+
+// an optionally signed integer, parsed as T
+pub fn number<'a, T: FromStr>() -> impl Parser<'a, T> {
+    move |input: &'a str| {
+        let unsigned = input.strip_prefix(['-', '+']).unwrap_or(input);
+        let digits = unsigned
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(unsigned.len());
+        if digits == 0 {
+            return Err(Expected::new(input, "a number"));
+        }
+        let (number, rest) = input.split_at(input.len() - unsigned.len() + digits);
+        match number.parse() {
+            Ok(value) => Ok((value, rest)),
+            Err(_) => Err(Expected::new(
+                input,
+                format!("a number that fits in `{}`", short_type_name::<T>()),
+            )),
+        }
+    }
+}
+
+// any amount of whitespace, including none
+pub fn whitespace<'a>() -> impl Parser<'a, &'a str> {
+    take_while(char::is_whitespace)
+}
+
+// parser, ignoring whitespace around it
+pub fn padded<'a, T>(parser: impl Parser<'a, T>) -> impl Parser<'a, T> {
+    delimited(whitespace(), parser, whitespace())
+}
+
+// prefix then parser, keeping the value of parser
+pub fn preceded<'a, A, T>(
+    prefix: impl Parser<'a, A>,
+    parser: impl Parser<'a, T>,
+) -> impl Parser<'a, T> {
+    prefix.then(parser).map(|(_, value)| value)
+}
+
+// parser then suffix, keeping the value of parser
+pub fn terminated<'a, T, B>(
+    parser: impl Parser<'a, T>,
+    suffix: impl Parser<'a, B>,
+) -> impl Parser<'a, T> {
+    parser.then(suffix).map(|(value, _)| value)
+}
+
+// parser between open and close, e.g. brackets
+pub fn delimited<'a, A, T, B>(
+    open: impl Parser<'a, A>,
+    parser: impl Parser<'a, T>,
+    close: impl Parser<'a, B>,
+) -> impl Parser<'a, T> {
+    preceded(open, terminated(parser, close))
+}
+
+// zero or more items with a separator between each
+pub fn separated<'a, T, S>(
+    item: impl Parser<'a, T>,
+    separator: impl Parser<'a, S>,
+) -> impl Parser<'a, Vec<T>> {
+    move |input: &'a str| {
+        let mut items = vec![];
+        let mut rest = match item.parse_next(input) {
+            Ok((first, rest)) => {
+                items.push(first);
+                rest
+            }
+            // an item that failed part way through is an error, not an empty list
+            Err(expected) if expected.rest.len() < input.len() => return Err(expected),
+            Err(_) => return Ok((items, input)),
+        };
+        while let Ok((_, after_separator)) = separator.parse_next(rest) {
+            let (next, after_item) = item.parse_next(after_separator)?;
+            items.push(next);
+            rest = after_item;
+        }
+        Ok((items, rest))
+    }
+}
+
+// zero or more repetitions of parser
+pub fn many<'a, T>(parser: impl Parser<'a, T>) -> impl Parser<'a, Vec<T>> {
+    move |input: &'a str| {
+        let mut items = vec![];
+        let mut rest = input;
+        loop {
+            match parser.parse_next(rest) {
+                // stop when the parser matches without consuming anything
+                Ok((_, after)) if after.len() == rest.len() => return Ok((items, rest)),
+                Ok((item, after)) => {
+                    items.push(item);
+                    rest = after;
+                }
+                Err(expected) if expected.rest.len() < rest.len() => return Err(expected),
+                Err(_) => return Ok((items, rest)),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    enum Tree {
+        Leaf(i32),
+        Node(Vec<Tree>),
+    }
+
+    fn tree(input: &str) -> Parsed<'_, Tree> {
+        number()
+            .map(Tree::Leaf)
+            .or(delimited(
+                padded(literal("[")),
+                separated(padded(tree), literal(",")),
+                literal("]"),
+            )
+            .map(Tree::Node))
+            .parse_next(input)
+    }
+
+    #[test]
+    fn parses_recursive_lists() -> Result<()> {
+        use Tree::*;
+
+        assert_eq!(
+            Node(vec![Leaf(1), Node(vec![Leaf(-2), Node(vec![])]), Leaf(3)]),
+            parse_with("[1, [ -2,[]], 3]\n", tree)?
+        );
+        assert_eq!(Leaf(7), parse_with("7", tree)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn sequences_and_repetition() -> Result<()> {
+        let assignment = word()
+            .then(preceded(padded(literal("=")), number::<u8>()))
+            .map(|(name, value)| (name.to_string(), value));
+        let assignments = many(terminated(assignment, whitespace()));
+
+        assert_eq!(
+            vec![("a".to_string(), 1), ("bc".to_string(), 20)],
+            parse_with("a = 1 bc=20", assignments)?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn errors_point_at_failure() {
+        let err = parse_with("[1,2,x]", tree).unwrap_err();
+        let err = err.downcast_ref::<ParseError>().unwrap();
+
+        assert_eq!("Tree", err.target);
+        assert_eq!(5..6, err.columns);
+        assert_eq!("expected a number or `[`", err.message);
+
+        let err = parse_with("[1,2]\n[3]", tree).unwrap_err();
+        let err = err.downcast_ref::<ParseError>().unwrap();
+
+        assert_eq!(Some(2), err.line);
+        assert_eq!(0..1, err.columns);
+        assert_eq!("expected end of input", err.message);
+
+        let err = parse_with("300", number::<u8>()).unwrap_err();
+        assert!(err.to_string().contains("a number that fits in `u8`"));
+    }
+}
@@ -0,0 +1,280 @@
+// enumerating compositions, multisets and subsets. These are streaming iterators: advance returns
+// a slice into a buffer that's reused for every item, so nothing is allocated per item. They're
+// used with while let instead of for:
+//
+//   let mut amounts = Compositions::strong(100, 4);
+//   while let Some(parts) = amounts.advance() {
+//       // parts is a &[usize] of 4 parts, each at least 1, adding up to 100
+//   }
+//
+// the item has to be dropped before the next call to advance
+use crate::Int;
+
+// the ways to write total as an ordered sum of parts. Weak compositions allow parts of 0, strong
+// compositions don't. Compositions come out in lexicographic order
+#[derive(Clone, Debug)]
+pub struct Compositions {
+    total: usize,
+    min_part: usize,
+    parts: Vec<usize>,
+    // the sum of every part but the last, which takes whatever is left over
+    leading_sum: usize,
+    started: bool,
+    done: bool,
+}
+
+impl Compositions {
+    pub fn weak(total: usize, count: usize) -> Self {
+        Compositions::with_min_part(total, count, 0)
+    }
+
+    pub fn strong(total: usize, count: usize) -> Self {
+        Compositions::with_min_part(total, count, 1)
+    }
+
+    fn with_min_part(total: usize, count: usize, min_part: usize) -> Self {
+        // with no parts the only composition is the empty one, of 0
+        let possible = if count == 0 {
+            total == 0
+        } else {
+            count * min_part <= total
+        };
+        Compositions {
+            total,
+            min_part,
+            parts: vec![min_part; count],
+            leading_sum: count.saturating_sub(1) * min_part,
+            started: false,
+            done: !possible,
+        }
+    }
+
+    pub fn advance(&mut self) -> Option<&[usize]> {
+        if self.done {
+            return None;
+        }
+        if self.started && !self.increment() {
+            self.done = true;
+            return None;
+        }
+        self.started = true;
+        if let Some(last) = self.parts.last_mut() {
+            *last = self.total - self.leading_sum;
+        }
+        Some(&self.parts)
+    }
+
+    // counts up through the leading parts like an odometer, as long as the last part can still
+    // cover what's left
+    fn increment(&mut self) -> bool {
+        let leading = self.parts.len().saturating_sub(1);
+        for ix in (0..leading).rev() {
+            if self.leading_sum + 1 + self.min_part <= self.total {
+                self.parts[ix] += 1;
+                self.leading_sum += 1;
+                return true;
+            }
+            self.leading_sum -= self.parts[ix] - self.min_part;
+            self.parts[ix] = self.min_part;
+        }
+        false
+    }
+}
+
+// every way to choose size items from item_count items when the same item can be chosen more than
+// once, as non-decreasing item indexes
+#[derive(Clone, Debug)]
+pub struct CombinationsWithRepetition {
+    item_count: usize,
+    indexes: Vec<usize>,
+    started: bool,
+    done: bool,
+}
+
+impl CombinationsWithRepetition {
+    pub fn new(item_count: usize, size: usize) -> Self {
+        CombinationsWithRepetition {
+            item_count,
+            indexes: vec![0; size],
+            started: false,
+            done: item_count == 0 && size > 0,
+        }
+    }
+
+    pub fn advance(&mut self) -> Option<&[usize]> {
+        if self.done {
+            return None;
+        }
+        if self.started {
+            let Some(ix) = self
+                .indexes
+                .iter()
+                .rposition(|&index| index + 1 < self.item_count)
+            else {
+                self.done = true;
+                return None;
+            };
+            let next = self.indexes[ix] + 1;
+            self.indexes[ix..].fill(next);
+        }
+        self.started = true;
+        Some(&self.indexes)
+    }
+}
+
+// every subset of values adding up to target, as increasing indexes into values. Values can't be
+// negative, since branches are abandoned as soon as they go over target
+#[derive(Clone, Debug)]
+pub struct SubsetsWithSum<'a, T> {
+    values: &'a [T],
+    target: T,
+    chosen: Vec<usize>,
+    sum: T,
+    next_ix: usize,
+    started: bool,
+}
+
+impl<'a, T: Int> SubsetsWithSum<'a, T> {
+    pub fn new(values: &'a [T], target: T) -> Self {
+        SubsetsWithSum {
+            values,
+            target,
+            chosen: vec![],
+            sum: T::ZERO,
+            next_ix: 0,
+            started: false,
+        }
+    }
+
+    pub fn advance(&mut self) -> Option<&[usize]> {
+        if !self.started {
+            self.started = true;
+            if self.target == T::ZERO {
+                return Some(&self.chosen);
+            }
+        }
+        // a depth first search through which values to include, picking up where the last
+        // subset left off
+        loop {
+            if self.next_ix < self.values.len() {
+                let ix = self.next_ix;
+                self.next_ix += 1;
+                if let Some(sum) = self.sum.checked_add(self.values[ix]) {
+                    if sum <= self.target {
+                        self.chosen.push(ix);
+                        self.sum = sum;
+                        if sum == self.target {
+                            return Some(&self.chosen);
+                        }
+                    }
+                }
+            } else {
+                let last = self.chosen.pop()?;
+                self.sum = self.sum - self.values[last];
+                self.next_ix = last + 1;
+            }
+        }
+    }
+}
+
+// counts[size] is how many subsets of values with size items add up to target. Values can't be
+// negative
+pub fn count_subsets_by_size<T: Int>(values: &[T], target: T) -> Vec<usize> {
+    let mut counts = vec![0; values.len() + 1];
+    let mut subsets = SubsetsWithSum::new(values, target);
+    while let Some(subset) = subsets.advance() {
+        counts[subset.len()] += 1;
+    }
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn collect(mut advance: impl FnMut() -> Option<Vec<usize>>) -> Vec<Vec<usize>> {
+        let mut items = vec![];
+        while let Some(item) = advance() {
+            items.push(item);
+        }
+        items
+    }
+
+    #[test]
+    fn compositions() {
+        let mut weak = Compositions::weak(2, 3);
+        let all = collect(|| weak.advance().map(<[usize]>::to_vec));
+        assert_eq!(
+            vec![
+                vec![0, 0, 2],
+                vec![0, 1, 1],
+                vec![0, 2, 0],
+                vec![1, 0, 1],
+                vec![1, 1, 0],
+                vec![2, 0, 0],
+            ],
+            all
+        );
+
+        // C(99, 3) ways to split 100 teaspoons between 4 ingredients
+        let mut strong = Compositions::strong(100, 4);
+        let mut count = 0;
+        while let Some(parts) = strong.advance() {
+            assert!(parts.iter().all(|&part| part >= 1));
+            assert_eq!(100, parts.iter().sum::<usize>());
+            count += 1;
+        }
+        assert_eq!(156_849, count);
+
+        assert_eq!(Some(&[5][..]), Compositions::strong(5, 1).advance());
+        assert_eq!(None, Compositions::strong(2, 3).advance());
+        assert_eq!(Some(&[][..]), Compositions::weak(0, 0).advance());
+        assert_eq!(None, Compositions::weak(1, 0).advance());
+    }
+
+    #[test]
+    fn combinations_with_repetition() {
+        let mut combinations = CombinationsWithRepetition::new(3, 2);
+        let all = collect(|| combinations.advance().map(<[usize]>::to_vec));
+        assert_eq!(
+            vec![
+                vec![0, 0],
+                vec![0, 1],
+                vec![0, 2],
+                vec![1, 1],
+                vec![1, 2],
+                vec![2, 2],
+            ],
+            all
+        );
+
+        assert_eq!(None, CombinationsWithRepetition::new(0, 1).advance());
+        assert_eq!(
+            Some(&[][..]),
+            CombinationsWithRepetition::new(0, 0).advance()
+        );
+    }
+
+    #[test]
+    fn subset_sums() {
+        // the containers from 2015 day 17
+        let containers = [20, 15, 10, 5, 5];
+        let mut subsets = SubsetsWithSum::new(&containers, 25);
+        let all = collect(|| subsets.advance().map(<[usize]>::to_vec));
+        assert_eq!(vec![vec![0, 3], vec![0, 4], vec![1, 2], vec![1, 3, 4]], all);
+
+        assert_eq!(
+            vec![0, 0, 3, 1, 0, 0],
+            count_subsets_by_size(&containers, 25)
+        );
+        assert_eq!(
+            vec![1, 0, 0, 0, 0, 0],
+            count_subsets_by_size(&containers, 0)
+        );
+        assert_eq!(1, count_subsets_by_size(&containers, 55)[5]);
+        assert_eq!(
+            0,
+            count_subsets_by_size(&containers, 56).iter().sum::<usize>()
+        );
+    }
+}
@@ -0,0 +1,381 @@
+// exact intersections of lines, rays and segments with integer coordinates. Everything is worked
+// out with i128 rationals, so coordinates as large as 2023 day 24's 15 digit ones don't lose
+// precision the way they would with f64. Coordinates so large that working it out would overflow
+// an i128 are an error rather than a wrong answer
+//
+// a line is origin + t * direction. Limiting t to t >= 0 makes it a ray, and 0 <= t <= 1 makes it
+// the segment from origin to origin + direction
+use anyhow::*;
+
+use crate::{IPoint2D, IPoint3D, IVector3D, Rational};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LineIntersection<const N: usize> {
+    // the lines run the same way and never meet
+    Parallel,
+    // only in 3D: the lines aren't parallel, but don't meet either
+    Skew,
+    // the lines are the same line
+    Collinear,
+    // the lines meet at point, which is t along the first line and s along the second
+    Crossing {
+        point: [Rational<i128>; N],
+        t: Rational<i128>,
+        s: Rational<i128>,
+    },
+}
+
+impl<const N: usize> LineIntersection<N> {
+    // where the lines meet, if it's ahead of (or at) both origins
+    pub fn ray_crossing(&self) -> Option<&[Rational<i128>; N]> {
+        match self {
+            LineIntersection::Crossing { point, t, s } if !is_negative(t) && !is_negative(s) => {
+                Some(point)
+            }
+            _ => None,
+        }
+    }
+
+    // where the lines meet, if it's on both segments
+    pub fn segment_crossing(&self) -> Option<&[Rational<i128>; N]> {
+        let one = Rational::integer(1);
+        match self {
+            LineIntersection::Crossing { point, t, s }
+                if !is_negative(t) && !is_negative(s) && *t <= one && *s <= one =>
+            {
+                Some(point)
+            }
+            _ => None,
+        }
+    }
+}
+
+fn is_negative(value: &Rational<i128>) -> bool {
+    *value < Rational::zero()
+}
+
+// whether point is inside the box from min to max, including its edges
+pub fn within_box<const N: usize>(
+    point: &[Rational<i128>; N],
+    min: [i64; N],
+    max: [i64; N],
+) -> bool {
+    (0..N).all(|axis| {
+        point[axis] >= Rational::integer(min[axis].into())
+            && point[axis] <= Rational::integer(max[axis].into())
+    })
+}
+
+// 2D points and offsets for lines. These are i64, since IPoint2D's i32 coordinates are too small
+// for the puzzles that need exact intersections
+#[derive(Copy, Clone, Default, Debug, PartialEq, Eq, Hash)]
+pub struct IVector2D {
+    pub dx: i64,
+    pub dy: i64,
+}
+
+impl From<IPoint2D> for IVector2D {
+    fn from(pt: IPoint2D) -> Self {
+        IVector2D {
+            dx: pt.x.into(),
+            dy: pt.y.into(),
+        }
+    }
+}
+
+// origin is measured from 0,0
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Line2D {
+    pub origin: IVector2D,
+    pub direction: IVector2D,
+}
+
+impl Line2D {
+    // the segment from start to end
+    pub fn segment(start: impl Into<IVector2D>, end: impl Into<IVector2D>) -> Line2D {
+        let (start, end) = (start.into(), end.into());
+        Line2D {
+            origin: start,
+            direction: IVector2D {
+                dx: end.dx - start.dx,
+                dy: end.dy - start.dy,
+            },
+        }
+    }
+
+    pub fn point_at(&self, t: &Rational<i128>) -> Result<[Rational<i128>; 2]> {
+        point_at(
+            [self.origin.dx, self.origin.dy].map(i128::from),
+            [self.direction.dx, self.direction.dy].map(i128::from),
+            t,
+        )
+    }
+
+    pub fn intersect(&self, other: &Line2D) -> Result<LineIntersection<2>> {
+        let d = [self.direction.dx, self.direction.dy].map(i128::from);
+        let e = [other.direction.dx, other.direction.dy].map(i128::from);
+        let w = [
+            i128::from(other.origin.dx) - i128::from(self.origin.dx),
+            i128::from(other.origin.dy) - i128::from(self.origin.dy),
+        ];
+
+        let denominator = cross_2d(d, e)?;
+        if denominator == 0 {
+            return Ok(if cross_2d(w, d)? == 0 {
+                LineIntersection::Collinear
+            } else {
+                LineIntersection::Parallel
+            });
+        }
+        crossing(
+            |t| self.point_at(t),
+            cross_2d(w, e)?,
+            cross_2d(w, d)?,
+            denominator,
+        )
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Line3D {
+    pub origin: IPoint3D,
+    pub direction: IVector3D,
+}
+
+impl Line3D {
+    // the segment from start to end
+    pub fn segment(start: IPoint3D, end: IPoint3D) -> Line3D {
+        Line3D {
+            origin: start,
+            direction: IVector3D {
+                dx: end.x - start.x,
+                dy: end.y - start.y,
+                dz: end.z - start.z,
+            },
+        }
+    }
+
+    pub fn point_at(&self, t: &Rational<i128>) -> Result<[Rational<i128>; 3]> {
+        point_at(
+            [self.origin.x, self.origin.y, self.origin.z].map(i128::from),
+            [self.direction.dx, self.direction.dy, self.direction.dz].map(i128::from),
+            t,
+        )
+    }
+
+    pub fn intersect(&self, other: &Line3D) -> Result<LineIntersection<3>> {
+        let d = [self.direction.dx, self.direction.dy, self.direction.dz].map(i128::from);
+        let e = [other.direction.dx, other.direction.dy, other.direction.dz].map(i128::from);
+        let w = [
+            i128::from(other.origin.x) - i128::from(self.origin.x),
+            i128::from(other.origin.y) - i128::from(self.origin.y),
+            i128::from(other.origin.z) - i128::from(self.origin.z),
+        ];
+
+        let normal = cross_3d(d, e)?;
+        if normal == [0; 3] {
+            return Ok(if cross_3d(w, d)? == [0; 3] {
+                LineIntersection::Collinear
+            } else {
+                LineIntersection::Parallel
+            });
+        }
+        if dot_3d(w, normal)? != 0 {
+            return Ok(LineIntersection::Skew);
+        }
+        // crossing both sides of origin + t * d = other.origin + s * e with e (or d) leaves a
+        // multiple of the normal, which can be divided out by dotting with the normal
+        crossing(
+            |t| self.point_at(t),
+            dot_3d(cross_3d(w, e)?, normal)?,
+            dot_3d(cross_3d(w, d)?, normal)?,
+            dot_3d(normal, normal)?,
+        )
+    }
+}
+
+fn too_large() -> Error {
+    anyhow!("line coordinates are too large to intersect exactly")
+}
+
+fn cross_2d(a: [i128; 2], b: [i128; 2]) -> Result<i128> {
+    a[0].checked_mul(b[1])
+        .zip(a[1].checked_mul(b[0]))
+        .and_then(|(ab, ba)| ab.checked_sub(ba))
+        .ok_or_else(too_large)
+}
+
+fn cross_3d(a: [i128; 3], b: [i128; 3]) -> Result<[i128; 3]> {
+    Ok([
+        cross_2d([a[1], a[2]], [b[1], b[2]])?,
+        cross_2d([a[2], a[0]], [b[2], b[0]])?,
+        cross_2d([a[0], a[1]], [b[0], b[1]])?,
+    ])
+}
+
+fn dot_3d(a: [i128; 3], b: [i128; 3]) -> Result<i128> {
+    a.iter()
+        .zip(b)
+        .try_fold(0_i128, |sum, (a, b)| sum.checked_add(a.checked_mul(b)?))
+        .ok_or_else(too_large)
+}
+
+fn point_at<const N: usize>(
+    origin: [i128; N],
+    direction: [i128; N],
+    t: &Rational<i128>,
+) -> Result<[Rational<i128>; N]> {
+    let mut point = origin.map(Rational::integer);
+    for (coordinate, delta) in point.iter_mut().zip(direction) {
+        *coordinate = t
+            .checked_mul(&Rational::integer(delta))
+            .and_then(|offset| coordinate.checked_add(&offset))
+            .ok_or_else(too_large)?;
+    }
+    Ok(point)
+}
+
+// denominator is never zero here, the caller has already checked for parallel lines
+fn crossing<const N: usize, F>(
+    point_at: F,
+    t_numerator: i128,
+    s_numerator: i128,
+    denominator: i128,
+) -> Result<LineIntersection<N>>
+where
+    F: Fn(&Rational<i128>) -> Result<[Rational<i128>; N]>,
+{
+    let t = Rational::new(t_numerator, denominator)?;
+    let s = Rational::new(s_numerator, denominator)?;
+    Ok(LineIntersection::Crossing {
+        point: point_at(&t)?,
+        t,
+        s,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ipt, ipt3};
+
+    fn rational(num: i128, den: i128) -> Rational<i128> {
+        Rational::new(num, den).unwrap()
+    }
+
+    #[test]
+    fn intersects_2d() -> Result<()> {
+        let a = Line2D::segment(ipt(0, 0), ipt(4, 4));
+        let b = Line2D::segment(ipt(0, 3), ipt(3, 0));
+        let crossing = a.intersect(&b)?;
+        let half = [rational(3, 2), rational(3, 2)];
+        assert_eq!(
+            LineIntersection::Crossing {
+                point: half.clone(),
+                t: rational(3, 8),
+                s: rational(1, 2),
+            },
+            crossing
+        );
+        assert_eq!(Some(&half), crossing.segment_crossing());
+        assert!(within_box(&half, [1, 1], [2, 2]));
+        assert!(!within_box(&half, [2, 0], [3, 3]));
+
+        // the lines cross, but past the end of the shorter segment
+        let short = Line2D::segment(ipt(0, 3), ipt(1, 2));
+        assert!(a.intersect(&short)?.segment_crossing().is_none());
+        assert!(a.intersect(&short)?.ray_crossing().is_some());
+        // and behind the start of the ray going the other way
+        let backwards = Line2D::segment(ipt(1, 2), ipt(0, 3));
+        assert!(a.intersect(&backwards)?.ray_crossing().is_none());
+
+        let shifted = Line2D::segment(ipt(1, 0), ipt(5, 4));
+        assert_eq!(LineIntersection::Parallel, a.intersect(&shifted)?);
+        let overlapping = Line2D::segment(ipt(-2, -2), ipt(1, 1));
+        assert_eq!(LineIntersection::Collinear, a.intersect(&overlapping)?);
+
+        // hailstones from 2023 day 24, big enough that f64 can't represent where they cross
+        // exactly
+        let hail = |dx, dy, vx, vy| Line2D {
+            origin: IVector2D { dx, dy },
+            direction: IVector2D { dx: vx, dy: vy },
+        };
+        let first = hail(300_000_000_000_000, 200_000_000_000_000, 2, -1);
+        let second = hail(300_000_000_000_000, 100_000_000_000_000, 1, 1);
+        let LineIntersection::Crossing { point, t, s } = first.intersect(&second)? else {
+            panic!("hail should cross");
+        };
+        assert_eq!(rational(100_000_000_000_000, 3), t);
+        assert_eq!(
+            [
+                rational(1_100_000_000_000_000, 3),
+                rational(500_000_000_000_000, 3)
+            ],
+            point
+        );
+        assert_eq!(first.point_at(&t)?, second.point_at(&s)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn intersects_3d() -> Result<()> {
+        let a = Line3D::segment(ipt3(0, 0, 0), ipt3(2, 2, 2));
+        let b = Line3D::segment(ipt3(2, 0, 0), ipt3(0, 2, 2));
+        assert_eq!(
+            Some(&[1, 1, 1].map(Rational::integer)),
+            a.intersect(&b)?.segment_crossing()
+        );
+
+        let skew = Line3D::segment(ipt3(0, 1, 0), ipt3(1, 1, -1));
+        assert_eq!(LineIntersection::Skew, a.intersect(&skew)?);
+        let parallel = Line3D::segment(ipt3(0, 0, 1), ipt3(2, 2, 3));
+        assert_eq!(LineIntersection::Parallel, a.intersect(&parallel)?);
+        assert_eq!(LineIntersection::Collinear, a.intersect(&a)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn overflow_is_an_error() {
+        let big = |x, y, z| Line3D {
+            origin: ipt3(x, y, z),
+            direction: IVector3D {
+                dx: 1 << 40,
+                dy: -(1 << 40),
+                dz: 3,
+            },
+        };
+        let other = Line3D {
+            origin: ipt3(1 << 50, 0, 0),
+            direction: IVector3D {
+                dx: 5,
+                dy: 1 << 40,
+                dz: -(1 << 40),
+            },
+        };
+        assert!(big(0, 0, 0).intersect(&other).is_err());
+
+        let far = Line2D {
+            origin: IVector2D {
+                dx: i64::MAX,
+                dy: i64::MIN,
+            },
+            direction: IVector2D {
+                dx: i64::MIN,
+                dy: i64::MAX,
+            },
+        };
+        let across = Line2D {
+            origin: IVector2D {
+                dx: i64::MIN,
+                dy: i64::MIN,
+            },
+            direction: IVector2D {
+                dx: i64::MAX,
+                dy: 1,
+            },
+        };
+        assert!(far.intersect(&across).is_err());
+    }
+}
@@ -1,11 +1,17 @@
 use std::{
     cmp::Reverse,
     collections::VecDeque,
-    fmt::Debug,
+    fmt::{Debug, Write},
     ops::{Add, Deref, DerefMut},
+    str::FromStr,
 };
 
-use crate::{dijkstra, OptimizationState};
+use anyhow::*;
+
+use crate::{
+    dijkstra_with_path, hamiltonian_tour, parse_within, Objective, OptimizationState, Tour,
+    TourShape,
+};
 
 type NodeId = usize;
 
@@ -61,6 +67,102 @@ impl<N, E> Graph<N, E> {
     }
 }
 
+impl<N, E> Graph<N, E> {
+    // true when every edge has a matching edge going the other way. Only the connections are
+    // compared, the edges going each way can still have different weights
+    pub fn is_undirected(&self) -> bool {
+        self.is_mirrored(|_, _| true)
+    }
+
+    // true when every edge has an edge going the other way that's the same according to same
+    fn is_mirrored<F>(&self, same: F) -> bool
+    where
+        F: Fn(&E, &E) -> bool,
+    {
+        self.edges.iter().enumerate().all(|(from, edges)| {
+            edges.iter().all(|(to, edge)| {
+                self.edges[*to]
+                    .iter()
+                    .any(|(back, back_edge)| *back == from && same(edge, back_edge))
+            })
+        })
+    }
+
+    // Graphviz DOT output for debugging, render with `dot -Tsvg graph.dot > graph.svg`. Empty
+    // edge labels are omitted
+    pub fn to_dot<FN, FE>(&self, node_label: FN, edge_label: FE) -> String
+    where
+        FN: Fn(&N) -> String,
+        FE: Fn(&E) -> String,
+    {
+        self.to_dot_highlighted(node_label, edge_label, &[], &[])
+    }
+
+    pub fn to_dot_highlighted<FN, FE>(
+        &self,
+        node_label: FN,
+        edge_label: FE,
+        highlight_nodes: &[NodeId],
+        highlight_edges: &[(NodeId, NodeId)],
+    ) -> String
+    where
+        FN: Fn(&N) -> String,
+        FE: Fn(&E) -> String,
+    {
+        // bidirectional graphs are drawn with a single undirected line per pair, as long as both
+        // directions have the same label so that neither label is lost
+        let undirected = self.is_mirrored(|a, b| edge_label(a) == edge_label(b));
+        let (kind, connector) = if undirected {
+            ("graph", "--")
+        } else {
+            ("digraph", "->")
+        };
+        let is_highlighted_edge = |from: NodeId, to: NodeId| {
+            highlight_edges
+                .iter()
+                .any(|&edge| edge == (from, to) || (undirected && edge == (to, from)))
+        };
+
+        let mut dot = String::new();
+        writeln!(dot, "{kind} {{").unwrap();
+        for (id, node) in self.nodes.iter().enumerate() {
+            write!(dot, "  n{id} [label=\"{}\"", escape_dot(&node_label(node))).unwrap();
+            if highlight_nodes.contains(&id) {
+                write!(dot, ", {DOT_HIGHLIGHT}").unwrap();
+            }
+            writeln!(dot, "];").unwrap();
+        }
+        for (from, edges) in self.edges.iter().enumerate() {
+            for (to, edge) in edges {
+                if undirected && *to < from {
+                    continue;
+                }
+                let mut attributes = vec![];
+                let label = edge_label(edge);
+                if !label.is_empty() {
+                    attributes.push(format!("label=\"{}\"", escape_dot(&label)));
+                }
+                if is_highlighted_edge(from, *to) {
+                    attributes.push(DOT_HIGHLIGHT.to_string());
+                }
+                write!(dot, "  n{from} {connector} n{to}").unwrap();
+                if !attributes.is_empty() {
+                    write!(dot, " [{}]", attributes.join(", ")).unwrap();
+                }
+                writeln!(dot, ";").unwrap();
+            }
+        }
+        dot.push('}');
+        dot
+    }
+}
+
+const DOT_HIGHLIGHT: &str = "color=\"red\", penwidth=2";
+
+fn escape_dot(label: &str) -> String {
+    label.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
 impl<N, E> Graph<N, E>
 where
     N: Eq,
@@ -128,35 +230,59 @@ where
         distance.into_iter().max()
     }
 
+    // the nodes along a shortest path, starting with start and ending with end (both included,
+    // so shortest_path(a, a) is [a]). Edge weights must not be negative; with that the path
+    // can't visit a node twice, even across zero weight cycles
     pub fn shortest_path(&self, start: NodeId, end: NodeId) -> Option<Vec<NodeId>> {
-        dijkstra(
+        dijkstra_with_path(
             PathState::<E> {
                 node: start,
-                path: vec![],
                 length: Default::default(),
             },
             |state| {
-                let mut path = state.path.to_vec();
-                path.push(state.node);
                 let length_so_far = state.length;
-
                 self.edges[state.node]
                     .iter()
-                    .filter_map(move |(next, length)| {
-                        if path.contains(next) {
-                            None
-                        } else {
-                            Some(PathState {
-                                node: *next,
-                                path: path.clone(),
-                                length: length_so_far + *length,
-                            })
-                        }
+                    .map(move |(next, length)| PathState {
+                        node: *next,
+                        length: length_so_far + *length,
                     })
             },
             |state| state.node == end,
         )
-        .map(|state| state.path)
+        .map(|(_, path)| path)
+    }
+
+    // edge weights as a dense matrix, None where nodes aren't connected. When there are several
+    // edges between the same nodes, the best one for objective is kept
+    pub fn distance_matrix(&self, objective: Objective) -> Vec<Vec<Option<E>>> {
+        let mut distances = vec![vec![None; self.nodes.len()]; self.nodes.len()];
+        for (from, edges) in self.edges.iter().enumerate() {
+            for &(to, length) in edges {
+                distances[from][to] = Some(match distances[from][to] {
+                    Some(prev) => objective.best(prev, length),
+                    None => length,
+                });
+            }
+        }
+        distances
+    }
+
+    // visits every node exactly once, see tour.rs
+    pub fn hamiltonian_tour(
+        &self,
+        shape: TourShape,
+        start: Option<NodeId>,
+        objective: Objective,
+    ) -> Option<Tour<E>> {
+        let distances = self.distance_matrix(objective);
+        hamiltonian_tour(
+            self.nodes.len(),
+            |a, b| distances[a][b],
+            shape,
+            start,
+            objective,
+        )
     }
 
     pub fn find_connected_group(&self, start: NodeId) -> Vec<NodeId> {
@@ -231,6 +357,39 @@ where
     }
 }
 
+impl<N> UnweightedGraphX<N>
+where
+    N: Eq + FromStr,
+    <N as FromStr>::Err: std::fmt::Display + 'static,
+{
+    // parses one node per line followed by its neighbors, in either of the usual puzzle formats:
+    //   a: b c d
+    //   a -> b, c
+    // when directed is false, every edge is added in both directions
+    pub fn from_adjacency_str(input: &str, directed: bool) -> Result<Self> {
+        let mut graph = Self::default();
+        for line in input.lines().filter(|line| !line.trim().is_empty()) {
+            let (node, neighbors) = line
+                .split_once("->")
+                .or_else(|| line.split_once(':'))
+                .ok_or_else(|| anyhow!("expected ':' or '->' in adjacency line \"{line}\""))?;
+            let node = parse_within(input, node.trim())?;
+            let neighbors = neighbors
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .filter(|name| !name.is_empty())
+                .map(|name| parse_within(input, name))
+                .collect::<Result<Vec<N>>>()?;
+
+            if directed {
+                graph.add_edges(node, neighbors);
+            } else {
+                graph.add_edges_bidi(node, neighbors);
+            }
+        }
+        Ok(graph)
+    }
+}
+
 impl<N> UnweightedGraphX<N> {
     // Here because this algorithm does not consider edge weights,
     // Doing so would make this a lot more memory intensive (IIRC it's NP-hard)
@@ -279,7 +438,6 @@ impl<N> DerefMut for UnweightedGraphX<N> {
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 struct PathState<T> {
     node: NodeId,
-    path: Vec<NodeId>,
     length: T,
 }
 
@@ -299,3 +457,81 @@ where
         Reverse(self.length)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_both_adjacency_formats() -> Result<()> {
+        let colon: UnweightedGraphX<String> =
+            UnweightedGraphX::from_adjacency_str("a: b c\nb: c\n", false)?;
+        let arrow: UnweightedGraphX<String> =
+            UnweightedGraphX::from_adjacency_str("a -> b, c\nb -> c\n", false)?;
+
+        for graph in [colon, arrow] {
+            assert_eq!(3, graph.node_count());
+            assert!(graph.is_undirected());
+            let a = graph.find_node_id(&"a".to_string()).unwrap();
+            assert_eq!(2, graph.neighbors(a).len());
+        }
+
+        let directed: UnweightedGraphX<String> =
+            UnweightedGraphX::from_adjacency_str("a -> b, c", true)?;
+        assert!(!directed.is_undirected());
+        assert!(UnweightedGraphX::<String>::from_adjacency_str("a b c", true).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn shortest_path_includes_both_ends() {
+        let mut graph: Graph<&str, u32> = Graph::default();
+        let nodes: Vec<NodeId> = ["a", "b", "c", "d", "e"]
+            .into_iter()
+            .map(|n| graph.add_node(n))
+            .collect();
+        graph.connect_bidi(nodes[0], nodes[1], 1);
+        // a zero weight cycle between b and c
+        graph.connect_bidi(nodes[1], nodes[2], 0);
+        graph.connect_bidi(nodes[2], nodes[3], 1);
+        graph.connect_bidi(nodes[0], nodes[3], 5);
+
+        assert_eq!(Some(vec![0, 1, 2, 3]), graph.shortest_path(0, 3));
+        assert_eq!(Some(vec![3, 2, 1, 0]), graph.shortest_path(3, 0));
+        assert_eq!(Some(vec![2]), graph.shortest_path(2, 2));
+        assert_eq!(None, graph.shortest_path(0, 4));
+    }
+
+    #[test]
+    fn dot_output() -> Result<()> {
+        let graph: UnweightedGraphX<String> =
+            UnweightedGraphX::from_adjacency_str("a: b c", false)?;
+        assert_eq!(
+            "graph {\n  n0 [label=\"a\"];\n  n1 [label=\"b\", color=\"red\", penwidth=2];\n  n2 [label=\"c\"];\n  n0 -- n1 [color=\"red\", penwidth=2];\n  n0 -- n2;\n}",
+            graph.to_dot_highlighted(|n| n.clone(), |_| String::new(), &[1], &[(1, 0)])
+        );
+
+        let mut directed: Graph<&str, u32> = Graph::default();
+        let a = directed.add_node("say \"hi\"");
+        let b = directed.add_node("b");
+        directed.connect(a, b, 7);
+        assert_eq!(
+            "digraph {\n  n0 [label=\"say \\\"hi\\\"\"];\n  n1 [label=\"b\"];\n  n0 -> n1 [label=\"7\"];\n}",
+            directed.to_dot(|n| n.to_string(), |e| e.to_string())
+        );
+
+        // edges both ways with different weights stay directed so both labels are drawn
+        directed.connect(b, a, 9);
+        assert!(directed.is_undirected());
+        assert_eq!(
+            "digraph {\n  n0 [label=\"say \\\"hi\\\"\"];\n  n1 [label=\"b\"];\n  n0 -> n1 [label=\"7\"];\n  n1 -> n0 [label=\"9\"];\n}",
+            directed.to_dot(|n| n.to_string(), |e| e.to_string())
+        );
+        assert!(directed
+            .to_dot(|n| n.to_string(), |_| String::new())
+            .starts_with("graph {"));
+
+        Ok(())
+    }
+}
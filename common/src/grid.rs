@@ -6,7 +6,7 @@ use std::hash::{Hash, Hasher};
 use std::ops::{Index, IndexMut};
 use std::str::FromStr;
 
-use crate::{dijkstra, wrap_parse_error, OptimizationState};
+use crate::{dijkstra, parse_within, OptimizationState};
 
 // contains helpers for grids and unsigned points
 // coordinates are laid out like this
@@ -691,15 +691,15 @@ impl<T> Grid2D<T> {
 impl<T> Grid2D<T>
 where
     T: FromStr,
-    <T as FromStr>::Err: std::fmt::Display,
+    <T as FromStr>::Err: std::fmt::Display + 'static,
 {
     // this is a special case where each grid item is only represented by a single character
     pub fn from_char_str(input: &str) -> Result<Grid2D<T>> {
         input
             .lines()
             .map(|x| -> Result<Vec<T>> {
-                x.chars()
-                    .map(|x| -> Result<T> { Ok(wrap_parse_error(x.to_string().parse())?) })
+                x.char_indices()
+                    .map(|(ix, c)| parse_within(input, &x[ix..ix + c.len_utf8()]))
                     .collect()
             })
             .collect()
@@ -709,9 +709,7 @@ where
         input
             .lines()
             .map(|x| -> Result<Vec<T>> {
-                x.split(delimiter)
-                    .map(|x| Ok(wrap_parse_error(x.to_string().parse())?))
-                    .collect()
+                x.split(delimiter).map(|x| parse_within(input, x)).collect()
             })
             .collect()
     }
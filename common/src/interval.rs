@@ -0,0 +1,331 @@
+// sets of integers stored as sorted, disjoint ranges, for puzzles that work with whole spans of
+// values at once (coverage of a row, ranges of seeds, ranges of part ratings)
+use std::cmp::{max, min};
+use std::ops::{Range, RangeInclusive};
+
+use anyhow::*;
+
+use crate::Int;
+
+// anything that can be used as a half-open range of T
+pub trait IntoInterval<T> {
+    fn into_interval(self) -> Range<T>;
+}
+
+impl<T: Int> IntoInterval<T> for Range<T> {
+    fn into_interval(self) -> Range<T> {
+        self
+    }
+}
+
+// panics if the range ends at T::MAX, since that can't be represented as a half-open range
+impl<T: Int> IntoInterval<T> for RangeInclusive<T> {
+    fn into_interval(self) -> Range<T> {
+        let (start, end) = self.into_inner();
+        if end < start {
+            start..start
+        } else {
+            start
+                ..end
+                    .checked_add(T::ONE)
+                    .expect("inclusive range ends at the maximum value")
+        }
+    }
+}
+
+// ranges are kept sorted and merged, so no two of them overlap or touch
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct IntervalSet<T> {
+    ranges: Vec<Range<T>>,
+}
+
+impl<T: Int> IntervalSet<T> {
+    pub fn new() -> Self {
+        IntervalSet { ranges: vec![] }
+    }
+
+    pub fn ranges(&self) -> &[Range<T>] {
+        &self.ranges
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    // the number of values in the set
+    pub fn covered_len(&self) -> T {
+        self.ranges
+            .iter()
+            .fold(T::ZERO, |total, range| total + (range.end - range.start))
+    }
+
+    pub fn insert(&mut self, range: impl IntoInterval<T>) {
+        let range = range.into_interval();
+        if range.is_empty() {
+            return;
+        }
+        // every range that overlaps or touches the new one is merged into it
+        let first = self.ranges.partition_point(|r| r.end < range.start);
+        let last = self.ranges.partition_point(|r| r.start <= range.end);
+        let merged = if first < last {
+            min(range.start, self.ranges[first].start)..max(range.end, self.ranges[last - 1].end)
+        } else {
+            range
+        };
+        self.ranges.splice(first..last, [merged]);
+    }
+
+    pub fn remove(&mut self, range: impl IntoInterval<T>) {
+        let range = range.into_interval();
+        if range.is_empty() {
+            return;
+        }
+        let first = self.ranges.partition_point(|r| r.end <= range.start);
+        let last = self.ranges.partition_point(|r| r.start < range.end);
+        if first == last {
+            return;
+        }
+        let before = self.ranges[first].start..range.start;
+        let after = range.end..self.ranges[last - 1].end;
+        self.ranges.splice(
+            first..last,
+            [before, after].into_iter().filter(|r| !r.is_empty()),
+        );
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.range_containing(value).is_some()
+    }
+
+    // the range in the set that value is part of
+    pub fn range_containing(&self, value: T) -> Option<Range<T>> {
+        let ix = self.ranges.partition_point(|r| r.end <= value);
+        self.ranges.get(ix).filter(|r| r.start <= value).cloned()
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        let mut union = self.clone();
+        for range in &other.ranges {
+            union.insert(range.clone());
+        }
+        union
+    }
+
+    pub fn intersect(&self, other: &Self) -> Self {
+        let mut ranges = vec![];
+        let (mut i, mut j) = (0, 0);
+        while i < self.ranges.len() && j < other.ranges.len() {
+            let (a, b) = (&self.ranges[i], &other.ranges[j]);
+            let overlap = max(a.start, b.start)..min(a.end, b.end);
+            if !overlap.is_empty() {
+                ranges.push(overlap);
+            }
+            if a.end < b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        IntervalSet { ranges }
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut difference = self.clone();
+        for range in &other.ranges {
+            difference.remove(range.clone());
+        }
+        difference
+    }
+
+    // everything within bounds that isn't in the set
+    pub fn complement(&self, within: impl IntoInterval<T>) -> Self {
+        IntervalSet {
+            ranges: self.gaps(within).collect(),
+        }
+    }
+
+    // the ranges within bounds that aren't in the set, in order
+    pub fn gaps(&self, within: impl IntoInterval<T>) -> impl Iterator<Item = Range<T>> + '_ {
+        let within = within.into_interval();
+        let mut next_start = within.start;
+        self.ranges
+            .iter()
+            .map(|range| range.start..range.end)
+            .chain([within.end..within.end])
+            .filter_map(move |range| {
+                let gap = next_start..min(range.start, within.end);
+                next_start = max(next_start, range.end);
+                (!gap.is_empty()).then_some(gap)
+            })
+    }
+
+    // (values below at, values at or above at)
+    pub fn split_at(&self, at: T) -> (Self, Self) {
+        let mut below = self.clone();
+        let mut above = self.clone();
+        if let Some(min) = self.ranges.first().map(|r| r.start) {
+            above.remove(min..at);
+        }
+        if let Some(max) = self.ranges.last().map(|r| r.end) {
+            below.remove(at..max);
+        }
+        (below, above)
+    }
+}
+
+impl<T: Int, R: IntoInterval<T>> FromIterator<R> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = R>>(iter: I) -> Self {
+        let mut set = IntervalSet::new();
+        for range in iter {
+            set.insert(range);
+        }
+        set
+    }
+}
+
+impl<T: Int> From<Range<T>> for IntervalSet<T> {
+    fn from(range: Range<T>) -> Self {
+        IntervalSet::from_iter([range])
+    }
+}
+
+impl<T: Int> From<RangeInclusive<T>> for IntervalSet<T> {
+    fn from(range: RangeInclusive<T>) -> Self {
+        IntervalSet::from_iter([range])
+    }
+}
+
+// a piecewise mapping where each source range is shifted to start somewhere else. Values outside
+// every source range map to themselves
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct IntervalMap<T> {
+    // (source, start of destination), sorted by source
+    shifts: Vec<(Range<T>, T)>,
+}
+
+impl<T: Int> IntervalMap<T> {
+    pub fn new() -> Self {
+        IntervalMap { shifts: vec![] }
+    }
+
+    // maps source to the range of the same length starting at dest_start
+    pub fn insert(&mut self, source: impl IntoInterval<T>, dest_start: T) -> Result<()> {
+        let source = source.into_interval();
+        if source.is_empty() {
+            return Ok(());
+        }
+        let ix = self.shifts.partition_point(|(s, _)| s.end <= source.start);
+        if let Some((existing, _)) = self.shifts.get(ix).filter(|(s, _)| s.start < source.end) {
+            bail!("{source:?} overlaps {existing:?}, which is already mapped");
+        }
+        self.shifts.insert(ix, (source, dest_start));
+        Ok(())
+    }
+
+    pub fn get(&self, value: T) -> T {
+        let ix = self.shifts.partition_point(|(s, _)| s.end <= value);
+        match self.shifts.get(ix) {
+            Some((source, dest_start)) if source.start <= value => {
+                *dest_start + (value - source.start)
+            }
+            _ => value,
+        }
+    }
+
+    // every value of set, mapped
+    pub fn map_set(&self, set: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut mapped = IntervalSet::new();
+        for range in set.ranges() {
+            let mut next = range.start;
+            let first = self.shifts.partition_point(|(s, _)| s.end <= range.start);
+            for (source, dest_start) in &self.shifts[first..] {
+                if source.start >= range.end {
+                    break;
+                }
+                // the part before this source maps to itself
+                if source.start > next {
+                    mapped.insert(next..source.start);
+                }
+                let overlap = max(next, source.start)..min(range.end, source.end);
+                mapped.insert(
+                    (*dest_start + (overlap.start - source.start))
+                        ..(*dest_start + (overlap.end - source.start)),
+                );
+                next = overlap.end;
+            }
+            mapped.insert(next..range.end);
+        }
+        mapped
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inserts_merge_and_removes_split() {
+        let mut set: IntervalSet<i32> = [5..=9, 0..=1, 2..=3].into_iter().collect();
+        assert_eq!(&[0..4, 5..10], set.ranges());
+        assert_eq!(9, set.covered_len());
+
+        set.insert(4..5);
+        assert_eq!(IntervalSet::from(0..10), set);
+
+        set.remove(3..6);
+        set.remove(8..=20);
+        assert_eq!(&[0..3, 6..8], set.ranges());
+        // an empty range isn't inserted
+        set.insert(5..5);
+        assert_eq!(2, set.ranges().len());
+
+        assert!(set.contains(6));
+        assert!(!set.contains(3));
+        assert_eq!(Some(6..8), set.range_containing(7));
+        assert_eq!(None, set.range_containing(-1));
+    }
+
+    #[test]
+    fn set_operations() {
+        let a = IntervalSet::from_iter([0..10, 20..30]);
+        let b = IntervalSet::from(5..25);
+
+        assert_eq!(IntervalSet::from(0..30), a.union(&b));
+        assert_eq!(&[5..10, 20..25], a.intersect(&b).ranges());
+        assert_eq!(&[0..5, 25..30], a.difference(&b).ranges());
+        assert_eq!(&[-5..0, 10..20, 30..35], a.complement(-5..35).ranges());
+
+        let gaps: Vec<_> = a.gaps(5..=25).collect();
+        assert_eq!(Some(&(10..20)), gaps.first());
+        assert_eq!(1, gaps.len());
+        assert_eq!(Some(0..4), IntervalSet::new().gaps(0..4).next());
+
+        let (below, above) = a.split_at(25);
+        assert_eq!(
+            (&[0..10, 20..25][..], IntervalSet::from(25..30).ranges()),
+            (below.ranges(), above.ranges())
+        );
+        let (below, above) = a.split_at(0);
+        assert!(below.is_empty());
+        assert_eq!(a, above);
+    }
+
+    #[test]
+    fn maps_values_and_ranges() -> Result<()> {
+        // seed-to-soil from 2023 day 5
+        let mut map = IntervalMap::new();
+        map.insert(98_usize..100, 50)?;
+        map.insert(50..98, 52)?;
+        assert!(map.insert(90..=91, 0).is_err());
+
+        assert_eq!(81, map.get(79));
+        assert_eq!(14, map.get(14));
+        assert_eq!(51, map.get(99));
+
+        // 40..50 and 100..101 aren't mapped, 98..100 lands on 50..52 and fills the gap
+        let seeds = IntervalSet::from_iter([79..93, 40..55, 97..101]);
+        assert_eq!(&[40..57, 81..95, 99..101], map.map_set(&seeds).ranges());
+
+        Ok(())
+    }
+}
@@ -1,4 +1,4 @@
-#![feature(pattern)]
+#![cfg_attr(feature = "pattern", feature(pattern))]
 
 use std::fmt::{self, Display};
 use std::io::{stdout, Write};
@@ -13,6 +13,18 @@ use reqwest::blocking::Client;
 mod parse;
 pub use parse::*;
 
+mod scan;
+pub use scan::*;
+
+mod combinator;
+pub use combinator::*;
+
+mod sections;
+pub use sections::*;
+
+mod bytes;
+pub use bytes::*;
+
 mod grid;
 pub use grid::*;
 
@@ -22,6 +34,9 @@ pub use sparse_grid::*;
 mod plot;
 pub use plot::*;
 
+mod ocr;
+pub use ocr::*;
+
 mod graph;
 pub use graph::*;
 
@@ -31,16 +46,25 @@ pub use legacy::*;
 mod math;
 pub use math::*;
 
+mod interval;
+pub use interval::*;
+
+mod combinatorics;
+pub use combinatorics::*;
+
 mod alg;
 pub use alg::*;
 
 mod three_d;
 pub use three_d::*;
 
-pub trait Solution: std::str::FromStr
-where
-    <Self as std::str::FromStr>::Err: std::fmt::Display,
-{
+mod geometry;
+pub use geometry::*;
+
+mod tour;
+pub use tour::*;
+
+pub trait Solution: ParseInput {
     type Part1: Display;
     type Part2: Display;
 
@@ -52,7 +76,7 @@ where
 
         println!("AOC {year} Day {day}");
 
-        let (mut solution, parse_time) = read_and_parse(parse_all::<Self>)?;
+        let (mut solution, parse_time) = read_and_parse_bytes(Self::parse_input)?;
 
         let part1_time =
             print_and_time("Part 1", || solution.part1()).context("failure in part 1")?;
@@ -113,15 +137,32 @@ fn download_input() -> Result<String> {
     }
 }
 
+fn read_input_bytes() -> Result<Vec<u8>> {
+    if Path::new("./input.txt").is_file() {
+        fs::read("./input.txt").context("could not read input.txt")
+    } else {
+        Ok(download_input()?.into_bytes())
+    }
+}
+
 fn read_and_parse<T, F>(parse: F) -> Result<(T, Duration)>
 where
     F: Fn(&str) -> Result<T>,
 {
-    let input = if Path::new("./input.txt").is_file() {
-        fs::read_to_string("./input.txt").context("could not read input.txt")?
-    } else {
-        download_input()?
-    };
+    let input = String::from_utf8(read_input_bytes()?).context("input.txt is not utf-8")?;
+
+    let start = Instant::now();
+    let input = parse(&input)?;
+    let parse_time = start.elapsed();
+
+    Ok((input, parse_time))
+}
+
+fn read_and_parse_bytes<T, F>(parse: F) -> Result<(T, Duration)>
+where
+    F: Fn(&[u8]) -> Result<T>,
+{
+    let input = read_input_bytes()?;
 
     let start = Instant::now();
     let input = parse(&input)?;
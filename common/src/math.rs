@@ -1,24 +1,134 @@
 use std::{
-    cmp::{max, min},
-    ops::{Add, Range, Rem},
+    cmp::{max, min, Ordering},
+    fmt::{self, Debug, Display},
+    hash::Hash,
+    ops::{Add, Div, Mul, Neg, Range, Rem, Sub},
 };
 
-use crate::IPoint2D;
+use anyhow::*;
 
-pub fn least_common_multiple(nums: &[usize]) -> usize {
-    let mut result = 1;
+use crate::{short_type_name, IPoint2D};
+
+// an arbitrary-precision integer, for intermediate products that don't fit in an i128
+#[cfg(feature = "bigint")]
+pub use num_bigint::BigInt;
+
+// the primitive integers puzzle math is done in, so helpers don't have to pick a width
+pub trait Int:
+    Copy
+    + Ord
+    + Hash
+    + Default
+    + Debug
+    + Display
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Rem<Output = Self>
+{
+    const ZERO: Self;
+    const ONE: Self;
+    const MIN: Self;
+    const MAX: Self;
+
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+    fn checked_sub(self, rhs: Self) -> Option<Self>;
+    fn checked_mul(self, rhs: Self) -> Option<Self>;
+    fn checked_div(self, rhs: Self) -> Option<Self>;
+    // the absolute value, unsigned types are returned as is
+    fn abs(self) -> Self;
+
+    // the checked operations, with an error saying what overflowed
+    fn try_add(self, rhs: Self) -> Result<Self> {
+        self.checked_add(rhs)
+            .ok_or_else(|| overflow::<Self>(format!("{self} + {rhs}")))
+    }
+
+    fn try_sub(self, rhs: Self) -> Result<Self> {
+        self.checked_sub(rhs)
+            .ok_or_else(|| overflow::<Self>(format!("{self} - {rhs}")))
+    }
+
+    fn try_mul(self, rhs: Self) -> Result<Self> {
+        self.checked_mul(rhs)
+            .ok_or_else(|| overflow::<Self>(format!("{self} * {rhs}")))
+    }
+
+    fn try_div(self, rhs: Self) -> Result<Self> {
+        self.checked_div(rhs)
+            .ok_or_else(|| overflow::<Self>(format!("{self} / {rhs}")))
+    }
+}
+
+pub trait SignedInt: Int + Neg<Output = Self> {}
+
+fn overflow<T>(operation: String) -> Error {
+    anyhow!("{operation} overflows {}", short_type_name::<T>())
+}
+
+macro_rules! impl_int {
+    ($abs:ident => $($t:ty),+) => {$(
+        impl Int for $t {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+            const MIN: Self = <$t>::MIN;
+            const MAX: Self = <$t>::MAX;
+
+            fn checked_add(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_add(self, rhs)
+            }
+
+            fn checked_sub(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_sub(self, rhs)
+            }
+
+            fn checked_mul(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_mul(self, rhs)
+            }
+
+            fn checked_div(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_div(self, rhs)
+            }
+
+            fn abs(self) -> Self {
+                impl_int!(@$abs self)
+            }
+        }
+    )+};
+    (@signed $value:ident) => { $value.abs() };
+    (@unsigned $value:ident) => { $value };
+}
+
+impl_int!(signed => i32, i64, i128, isize);
+impl_int!(unsigned => u32, u64, u128, usize);
+
+impl SignedInt for i32 {}
+impl SignedInt for i64 {}
+impl SignedInt for i128 {}
+impl SignedInt for isize {}
+
+// least common multiple of nums, which may overflow for large inputs. See
+// checked_least_common_multiple
+pub fn least_common_multiple<T: Int>(nums: &[T]) -> T {
+    let mut result = T::ONE;
     for &num in nums {
-        result = num * result / greatest_common_denominator(num, result);
+        result = num / greatest_common_denominator(num, result) * result;
     }
-    result
+    result.abs()
 }
 
-pub fn greatest_common_denominator<T>(a: T, b: T) -> T
-where
-    T: Default + Copy + Eq + Rem<Output = T>,
-{
-    if b == Default::default() {
-        return a;
+pub fn checked_least_common_multiple<T: Int>(nums: &[T]) -> Result<T> {
+    let mut result = T::ONE;
+    for &num in nums {
+        result = (num / greatest_common_denominator(num, result)).try_mul(result)?;
+    }
+    Ok(result.abs())
+}
+
+pub fn greatest_common_denominator<T: Int>(a: T, b: T) -> T {
+    if b == T::ZERO {
+        return a.abs();
     }
 
     greatest_common_denominator(b, a % b)
@@ -26,30 +136,765 @@ where
 
 // Shoelace formula.  https://en.wikipedia.org/wiki/Shoelace_formula
 //
-// There are two implementations because most won't need 64-bit values,
-// but some do
-pub fn shoelace_loop_area(vertices: Vec<IPoint2D>) -> i32 {
-    let mut area: i32 = 0;
+// T is the width the area is computed in, since most loops fit in an i32 but some need an i64
+pub fn shoelace_loop_area<T>(vertices: Vec<IPoint2D>) -> T
+where
+    T: SignedInt + From<i32>,
+{
+    let mut area = T::ZERO;
     let len = vertices.len();
     // there's probably a smarter windowing function I could do here, but I'm tired
     for i in 0..len {
         let j = (i + 1) % len;
-        area += vertices[i].x * vertices[j].y - vertices[j].x * vertices[i].y;
+        area = area + T::from(vertices[i].x) * T::from(vertices[j].y)
+            - T::from(vertices[j].x) * T::from(vertices[i].y);
     }
 
-    area.abs() / 2
+    area.abs() / T::from(2)
 }
 
-pub fn shoelace_loop_area_64(vertices: Vec<IPoint2D>) -> i64 {
-    let mut area: i64 = 0;
+pub fn checked_shoelace_loop_area<T>(vertices: Vec<IPoint2D>) -> Result<T>
+where
+    T: SignedInt + From<i32>,
+{
+    let mut area = T::ZERO;
     let len = vertices.len();
     for i in 0..len {
         let j = (i + 1) % len;
-        area += i64::from(vertices[i].x) * i64::from(vertices[j].y)
-            - i64::from(vertices[j].x) * i64::from(vertices[i].y);
+        let forward = T::from(vertices[i].x).try_mul(T::from(vertices[j].y))?;
+        let backward = T::from(vertices[j].x).try_mul(T::from(vertices[i].y))?;
+        area = area.try_add(forward.try_sub(backward)?)?;
+    }
+
+    Ok(area.abs() / T::from(2))
+}
+
+// a mod m in 0..m, for a positive m
+pub fn modulo<T: Int>(a: T, m: T) -> T {
+    let r = a % m;
+    if r < T::ZERO {
+        r + m
+    } else {
+        r
+    }
+}
+
+// (g, x, y) where g is the gcd of a and b, and a*x + b*y = g
+pub fn extended_gcd<T: SignedInt>(a: T, b: T) -> (T, T, T) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (T::ONE, T::ZERO);
+    let (mut old_y, mut y) = (T::ZERO, T::ONE);
+    while r != T::ZERO {
+        let quotient = old_r / r;
+        (old_r, r) = (r, old_r - quotient * r);
+        (old_x, x) = (x, old_x - quotient * x);
+        (old_y, y) = (y, old_y - quotient * y);
+    }
+    if old_r < T::ZERO {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+// x in 0..m where a*x = 1 mod m, None when a and m share a factor
+pub fn mod_inverse<T: SignedInt>(a: T, m: T) -> Option<T> {
+    let (g, x, _) = extended_gcd(modulo(a, m), m);
+    (g == T::ONE).then(|| modulo(x, m))
+}
+
+// a*b mod m without overflowing, for a and b in 0..m
+pub fn mul_mod<T: Int>(a: T, b: T, m: T) -> T {
+    if let Some(product) = a.checked_mul(b) {
+        return product % m;
+    }
+    // double and add, keeping every intermediate value under m
+    let add_mod = |a: T, b: T| if a >= m - b { a - (m - b) } else { a + b };
+    let two = T::ONE + T::ONE;
+    let (mut a, mut b, mut result) = (a, b, T::ZERO);
+    while b > T::ZERO {
+        if b % two == T::ONE {
+            result = add_mod(result, a);
+        }
+        a = add_mod(a, a);
+        b = b / two;
+    }
+    result
+}
+
+// base^exp mod m. A negative exp is an error, raise mod_inverse(base, m) to -exp for that
+pub fn mod_pow<T: Int>(base: T, exp: T, m: T) -> Result<T> {
+    if exp < T::ZERO {
+        bail!("mod_pow needs a non-negative exponent, got {exp}");
+    }
+    let two = T::ONE + T::ONE;
+    let (mut base, mut exp, mut result) = (modulo(base, m), exp, T::ONE % m);
+    while exp > T::ZERO {
+        if exp % two == T::ONE {
+            result = mul_mod(result, base, m);
+        }
+        base = mul_mod(base, base, m);
+        exp = exp / two;
+    }
+    Ok(result)
+}
+
+// solves x = residue mod modulus for every (residue, modulus), returning (x, lcm of the moduli)
+// with x in 0..lcm. Moduli don't have to be coprime, but then the congruences might contradict
+// each other, which is an error
+pub fn chinese_remainder<T: SignedInt>(congruences: &[(T, T)]) -> Result<(T, T)> {
+    let mut combined = (T::ZERO, T::ONE);
+    for &(residue, modulus) in congruences {
+        if modulus <= T::ZERO {
+            bail!("modulus {modulus} must be positive");
+        }
+        let (x, m) = combined;
+        let residue = modulo(residue, modulus);
+        let g = greatest_common_denominator(m, modulus);
+        let difference = residue - x;
+        if difference % g != T::ZERO {
+            bail!("x = {x} mod {m} and x = {residue} mod {modulus} have no solution");
+        }
+        // x + m*k = residue mod modulus, so k = difference/g * inverse(m/g) mod modulus/g
+        let step = modulus / g;
+        let inverse = mod_inverse(m / g, step).ok_or_else(|| anyhow!("{m} has no inverse"))?;
+        let k = mul_mod(modulo(difference / g, step), inverse, step);
+        let lcm = (m / g).try_mul(modulus)?;
+        combined = (modulo(x.try_add(m.try_mul(k)?)?, lcm), lcm);
+    }
+    Ok(combined)
+}
+
+// for cycles that each repeat every period starting at start, the first time they all line up
+// and how often they line up after that. When each cycle starts at its period, like the 2023 day 8
+// paths, that's the least common multiple both times
+pub fn least_common_multiple_with_offsets<T: SignedInt>(cycles: &[(T, T)]) -> Result<(T, T)> {
+    let (x, period) = chinese_remainder(cycles).context("cycles never line up")?;
+    let latest_start = cycles
+        .iter()
+        .map(|&(start, _)| start)
+        .max()
+        .unwrap_or(T::ZERO);
+    let first = if x >= latest_start {
+        x
+    } else {
+        // the first time at or after every cycle has started
+        let behind = latest_start - x;
+        let periods = (behind + period - T::ONE) / period;
+        x.try_add(periods.try_mul(period)?)?
+    };
+    Ok((first, period))
+}
+
+// is_prime[n] for every n up to limit, by the sieve of Eratosthenes
+pub fn prime_sieve(limit: usize) -> Vec<bool> {
+    let mut is_prime = vec![true; limit + 1];
+    for n in is_prime.iter_mut().take(2) {
+        *n = false;
+    }
+    let mut p = 2;
+    while p * p <= limit {
+        if is_prime[p] {
+            for multiple in (p * p..=limit).step_by(p) {
+                is_prime[multiple] = false;
+            }
+        }
+        p += 1;
     }
+    is_prime
+}
+
+pub fn primes_up_to(limit: usize) -> Vec<usize> {
+    prime_sieve(limit)
+        .into_iter()
+        .enumerate()
+        .filter_map(|(n, is_prime)| is_prime.then_some(n))
+        .collect()
+}
+
+// the prime factors of n with their exponents, smallest first, by trial division. Negative
+// numbers are factored as their absolute value, and 0 and 1 have no factors
+pub fn factorize<T: Int>(n: T) -> Vec<(T, u32)> {
+    let two = T::ONE + T::ONE;
+    let mut n = n.abs();
+    let mut factors = vec![];
+    if n == T::ZERO {
+        return factors;
+    }
+    let mut p = two;
+    // p * p <= n, without overflowing near T::MAX
+    while p <= n / p {
+        let mut exponent = 0;
+        while n % p == T::ZERO {
+            n = n / p;
+            exponent += 1;
+        }
+        if exponent > 0 {
+            factors.push((p, exponent));
+        }
+        p = if p == two { p + T::ONE } else { p + two };
+    }
+    if n > T::ONE {
+        factors.push((n, 1));
+    }
+    factors
+}
 
-    area.abs() / 2
+// every positive divisor of n, in order
+pub fn divisors<T: Int>(n: T) -> Vec<T> {
+    if n == T::ZERO {
+        return vec![];
+    }
+    let mut divisors = vec![T::ONE];
+    for (p, exponent) in factorize(n) {
+        let mut with_p = vec![];
+        for d in &divisors {
+            let mut power = *d;
+            for _ in 0..exponent {
+                power = power * p;
+                with_p.push(power);
+            }
+        }
+        divisors.append(&mut with_p);
+    }
+    divisors.sort_unstable();
+    divisors
+}
+
+// Euler's totient: how many of 1..=n are coprime with n
+pub fn totient<T: Int>(n: T) -> T {
+    factorize(n)
+        .into_iter()
+        .fold(n.abs(), |phi, (p, _)| phi / p * (p - T::ONE))
+}
+
+// sums[n] is the sum of the divisors of n for every n up to limit, in O(limit log limit). With
+// max_multiples, each divisor d only counts for its first max_multiples multiples (d, 2d, ... ),
+// like 2015 day 20's elves who each stop after 50 houses
+pub fn divisor_sum_sieve(limit: usize, max_multiples: Option<usize>) -> Vec<usize> {
+    let mut sums = vec![0; limit + 1];
+    for d in 1..=limit {
+        let multiples = (d..=limit).step_by(d);
+        match max_multiples {
+            Some(max) => multiples.take(max).for_each(|n| sums[n] += d),
+            None => multiples.for_each(|n| sums[n] += d),
+        }
+    }
+    sums
+}
+
+// integers a Rational can be built from: the signed primitives, and BigInt with the bigint
+// feature. Unlike Int these don't have to be Copy
+pub trait RationalInt:
+    Clone
+    + Ord
+    + Hash
+    + Debug
+    + Display
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Rem<Output = Self>
+    + Neg<Output = Self>
+{
+    fn zero() -> Self;
+    fn one() -> Self;
+    fn checked_add(&self, rhs: &Self) -> Option<Self>;
+    fn checked_sub(&self, rhs: &Self) -> Option<Self>;
+    fn checked_mul(&self, rhs: &Self) -> Option<Self>;
+}
+
+macro_rules! impl_rational_int {
+    ($($t:ty),+) => {$(
+        impl RationalInt for $t {
+            fn zero() -> Self {
+                0
+            }
+
+            fn one() -> Self {
+                1
+            }
+
+            fn checked_add(&self, rhs: &Self) -> Option<Self> {
+                <$t>::checked_add(*self, *rhs)
+            }
+
+            fn checked_sub(&self, rhs: &Self) -> Option<Self> {
+                <$t>::checked_sub(*self, *rhs)
+            }
+
+            fn checked_mul(&self, rhs: &Self) -> Option<Self> {
+                <$t>::checked_mul(*self, *rhs)
+            }
+        }
+    )+};
+}
+
+impl_rational_int!(i32, i64, i128, isize);
+
+#[cfg(feature = "bigint")]
+impl RationalInt for BigInt {
+    fn zero() -> Self {
+        BigInt::from(0)
+    }
+
+    fn one() -> Self {
+        BigInt::from(1)
+    }
+
+    fn checked_add(&self, rhs: &Self) -> Option<Self> {
+        Some(self + rhs)
+    }
+
+    fn checked_sub(&self, rhs: &Self) -> Option<Self> {
+        Some(self - rhs)
+    }
+
+    fn checked_mul(&self, rhs: &Self) -> Option<Self> {
+        Some(self * rhs)
+    }
+}
+
+fn rational_gcd<T: RationalInt>(a: &T, b: &T) -> T {
+    let (mut a, mut b) = (a.clone(), b.clone());
+    while b != T::zero() {
+        (a, b) = (b.clone(), a % b);
+    }
+    if a < T::zero() {
+        -a
+    } else {
+        a
+    }
+}
+
+// an exact fraction, always stored in lowest terms with a positive denominator. The operators
+// panic on overflow, use the checked methods where that's possible
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Rational<T> {
+    num: T,
+    den: T,
+}
+
+impl<T: RationalInt> Rational<T> {
+    pub fn new(num: T, den: T) -> Result<Self> {
+        if den == T::zero() {
+            bail!("{num}/{den} has a zero denominator");
+        }
+        Ok(Self::reduced(num, den))
+    }
+
+    pub fn integer(num: T) -> Self {
+        Rational { num, den: T::one() }
+    }
+
+    pub fn zero() -> Self {
+        Self::integer(T::zero())
+    }
+
+    pub fn numerator(&self) -> &T {
+        &self.num
+    }
+
+    pub fn denominator(&self) -> &T {
+        &self.den
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.num == T::zero()
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.den == T::one()
+    }
+
+    pub fn to_integer(&self) -> Option<T> {
+        self.is_integer().then(|| self.num.clone())
+    }
+
+    pub fn checked_add(&self, rhs: &Self) -> Option<Self> {
+        let g = rational_gcd(&self.den, &rhs.den);
+        let num = self
+            .num
+            .checked_mul(&(rhs.den.clone() / g.clone()))?
+            .checked_add(&rhs.num.checked_mul(&(self.den.clone() / g.clone()))?)?;
+        let den = (self.den.clone() / g).checked_mul(&rhs.den)?;
+        Some(Self::reduced(num, den))
+    }
+
+    pub fn checked_sub(&self, rhs: &Self) -> Option<Self> {
+        self.checked_add(&-rhs.clone())
+    }
+
+    pub fn checked_mul(&self, rhs: &Self) -> Option<Self> {
+        // cancel across first, so the products stay as small as possible
+        let g1 = rational_gcd(&self.num, &rhs.den);
+        let g2 = rational_gcd(&rhs.num, &self.den);
+        if g1 == T::zero() || g2 == T::zero() {
+            return Some(Self::zero());
+        }
+        let num = (self.num.clone() / g1.clone()).checked_mul(&(rhs.num.clone() / g2.clone()))?;
+        let den = (self.den.clone() / g2).checked_mul(&(rhs.den.clone() / g1))?;
+        Some(Self::reduced(num, den))
+    }
+
+    // None for division by zero too
+    pub fn checked_div(&self, rhs: &Self) -> Option<Self> {
+        if rhs.is_zero() {
+            return None;
+        }
+        self.checked_mul(&Self::reduced(rhs.den.clone(), rhs.num.clone()))
+    }
+
+    fn reduced(num: T, den: T) -> Self {
+        let g = rational_gcd(&num, &den);
+        let (num, den) = (num / g.clone(), den / g);
+        if den < T::zero() {
+            Rational {
+                num: -num,
+                den: -den,
+            }
+        } else {
+            Rational { num, den }
+        }
+    }
+}
+
+impl<T: RationalInt> From<T> for Rational<T> {
+    fn from(num: T) -> Self {
+        Self::integer(num)
+    }
+}
+
+impl<T: RationalInt> Display for Rational<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_integer() {
+            write!(f, "{}", self.num)
+        } else {
+            write!(f, "{}/{}", self.num, self.den)
+        }
+    }
+}
+
+macro_rules! impl_rational_op {
+    ($($trait:ident $fn:ident $checked:ident),+) => {$(
+        impl<T: RationalInt> $trait for Rational<T> {
+            type Output = Self;
+
+            fn $fn(self, rhs: Self) -> Self {
+                self.$checked(&rhs)
+                    .unwrap_or_else(|| panic!("{self} {} {rhs} failed", stringify!($fn)))
+            }
+        }
+    )+};
+}
+
+impl_rational_op!(Add add checked_add, Sub sub checked_sub, Mul mul checked_mul, Div div checked_div);
+
+impl<T: RationalInt> Neg for Rational<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Rational {
+            num: -self.num,
+            den: self.den,
+        }
+    }
+}
+
+impl<T: RationalInt> Ord for Rational<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        compare_fractions(
+            self.num.clone(),
+            self.den.clone(),
+            other.num.clone(),
+            other.den.clone(),
+        )
+    }
+}
+
+impl<T: RationalInt> PartialOrd for Rational<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+// a/b against c/d for positive b and d, by comparing whole parts then the fractions left over, so
+// nothing is multiplied and nothing overflows
+fn compare_fractions<T: RationalInt>(a: T, b: T, c: T, d: T) -> Ordering {
+    let floor_div = |n: T, d: T| {
+        let (q, r) = (n.clone() / d.clone(), n % d.clone());
+        if r < T::zero() {
+            (q - T::one(), r + d)
+        } else {
+            (q, r)
+        }
+    };
+    let (q1, r1) = floor_div(a, b.clone());
+    let (q2, r2) = floor_div(c, d.clone());
+    match (q1.cmp(&q2), r1 == T::zero(), r2 == T::zero()) {
+        (Ordering::Equal, true, true) => Ordering::Equal,
+        (Ordering::Equal, true, false) => Ordering::Less,
+        (Ordering::Equal, false, true) => Ordering::Greater,
+        // r1/b against r2/d is the same as d/r2 against b/r1
+        (Ordering::Equal, false, false) => compare_fractions(d, r2, b, r1),
+        (order, _, _) => order,
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LinearSolution<T> {
+    Unique(Vec<T>),
+    NoSolution,
+    InfinitelyMany,
+}
+
+fn check_system<T>(a: &[Vec<T>], b: &[T]) -> Result<usize> {
+    if a.len() != b.len() {
+        bail!("{} equations but {} constants", a.len(), b.len());
+    }
+    let unknowns = a.first().map_or(0, Vec::len);
+    if let Some(row) = a.iter().position(|row| row.len() != unknowns) {
+        bail!(
+            "equation {} has {} coefficients, expected {unknowns}",
+            row + 1,
+            a[row].len()
+        );
+    }
+    Ok(unknowns)
+}
+
+fn system_overflow() -> Error {
+    anyhow!("overflow solving linear system")
+}
+
+// solves a*x = b with Gauss-Jordan elimination
+pub fn solve_linear_system<T: RationalInt>(
+    a: &[Vec<Rational<T>>],
+    b: &[Rational<T>],
+) -> Result<LinearSolution<Rational<T>>> {
+    let unknowns = check_system(a, b)?;
+    let mut rows: Vec<Vec<Rational<T>>> = a
+        .iter()
+        .zip(b)
+        .map(|(row, constant)| row.iter().chain([constant]).cloned().collect())
+        .collect();
+
+    let mut rank = 0;
+    for col in 0..unknowns {
+        let Some(pivot) = (rank..rows.len()).find(|&r| !rows[r][col].is_zero()) else {
+            continue;
+        };
+        rows.swap(rank, pivot);
+        let pivot_value = rows[rank][col].clone();
+        for value in &mut rows[rank][col..] {
+            *value = value
+                .checked_div(&pivot_value)
+                .ok_or_else(system_overflow)?;
+        }
+        let pivot_row = rows[rank].clone();
+        for (r, row) in rows.iter_mut().enumerate() {
+            if r == rank || row[col].is_zero() {
+                continue;
+            }
+            let factor = row[col].clone();
+            for (value, pivot) in row[col..].iter_mut().zip(&pivot_row[col..]) {
+                *value = factor
+                    .checked_mul(pivot)
+                    .and_then(|scaled| value.checked_sub(&scaled))
+                    .ok_or_else(system_overflow)?;
+            }
+        }
+        rank += 1;
+    }
+
+    // every row past the rank has no coefficients left, so its constant has to be zero too
+    if rows[rank..].iter().any(|row| !row[unknowns].is_zero()) {
+        return Ok(LinearSolution::NoSolution);
+    }
+    if rank < unknowns {
+        return Ok(LinearSolution::InfinitelyMany);
+    }
+    Ok(LinearSolution::Unique(
+        rows.into_iter()
+            .take(unknowns)
+            .map(|mut row| row.swap_remove(unknowns))
+            .collect(),
+    ))
+}
+
+// solves a*x = b for integer coefficients with Bareiss elimination, which keeps every value an
+// integer until the final division
+pub fn solve_integer_system<T: RationalInt>(
+    a: &[Vec<T>],
+    b: &[T],
+) -> Result<LinearSolution<Rational<T>>> {
+    let unknowns = check_system(a, b)?;
+    let mut rows: Vec<Vec<T>> = a
+        .iter()
+        .zip(b)
+        .map(|(row, constant)| row.iter().chain([constant]).cloned().collect())
+        .collect();
+
+    let mut rank = 0;
+    let mut previous_pivot = T::one();
+    for col in 0..unknowns {
+        let Some(pivot) = (rank..rows.len()).find(|&r| rows[r][col] != T::zero()) else {
+            continue;
+        };
+        rows.swap(rank, pivot);
+        let pivot_value = rows[rank][col].clone();
+        for r in rank + 1..rows.len() {
+            for c in col + 1..=unknowns {
+                // always divides exactly
+                rows[r][c] = rows[r][c]
+                    .checked_mul(&pivot_value)
+                    .zip(rows[r][col].checked_mul(&rows[rank][c]))
+                    .and_then(|(kept, removed)| kept.checked_sub(&removed))
+                    .ok_or_else(system_overflow)?
+                    / previous_pivot.clone();
+            }
+            rows[r][col] = T::zero();
+        }
+        previous_pivot = pivot_value;
+        rank += 1;
+    }
+
+    if rows[rank..].iter().any(|row| row[unknowns] != T::zero()) {
+        return Ok(LinearSolution::NoSolution);
+    }
+    if rank < unknowns {
+        return Ok(LinearSolution::InfinitelyMany);
+    }
+
+    // full rank, so row i's pivot is in column i
+    let mut solution = vec![Rational::zero(); unknowns];
+    for i in (0..unknowns).rev() {
+        let mut remaining = Rational::integer(rows[i][unknowns].clone());
+        for (j, known) in solution.iter().enumerate().skip(i + 1) {
+            remaining = Rational::integer(rows[i][j].clone())
+                .checked_mul(known)
+                .and_then(|scaled| remaining.checked_sub(&scaled))
+                .ok_or_else(system_overflow)?;
+        }
+        solution[i] = remaining
+            .checked_div(&Rational::integer(rows[i][i].clone()))
+            .ok_or_else(system_overflow)?;
+    }
+    Ok(LinearSolution::Unique(solution))
+}
+
+// rows of differences between neighboring values, starting with values, until a row is all zero
+fn difference_table<T: Int>(values: &[T]) -> Result<Vec<Vec<T>>> {
+    if values.is_empty() {
+        bail!("can't extrapolate from no values");
+    }
+    let mut table = vec![values.to_vec()];
+    while let Some(row) = table.last().filter(|row| row.iter().any(|&v| v != T::ZERO)) {
+        let next = row
+            .windows(2)
+            .map(|pair| pair[1].try_sub(pair[0]))
+            .collect::<Result<Vec<_>>>()?;
+        if next.is_empty() {
+            break;
+        }
+        table.push(next);
+    }
+    Ok(table)
+}
+
+// the value after values, by finite differences
+pub fn extrapolate_next<T: Int>(values: &[T]) -> Result<T> {
+    difference_table(values)?
+        .iter()
+        .try_fold(T::ZERO, |next, row| next.try_add(row[row.len() - 1]))
+}
+
+// the value before values, by finite differences
+pub fn extrapolate_prev<T: Int>(values: &[T]) -> Result<T> {
+    difference_table(values)?
+        .iter()
+        .rev()
+        .try_fold(T::ZERO, |prev, row| row[0].try_sub(prev))
+}
+
+// the value at x of the polynomial through points, exactly
+pub fn lagrange_interpolate<T: RationalInt>(points: &[(T, T)], x: T) -> Result<Rational<T>> {
+    let overflow = || anyhow!("overflow interpolating at {x}");
+    let mut result = Rational::zero();
+    for (i, (xi, yi)) in points.iter().enumerate() {
+        let mut term = Rational::integer(yi.clone());
+        for (j, (xj, _)) in points.iter().enumerate() {
+            if i == j {
+                continue;
+            }
+            let from_x = x.checked_sub(xj).ok_or_else(overflow)?;
+            let from_xi = xi.checked_sub(xj).ok_or_else(overflow)?;
+            if from_xi == T::zero() {
+                bail!("can't interpolate through two points at x = {xi}");
+            }
+            term = term
+                .checked_mul(&Rational::new(from_x, from_xi)?)
+                .ok_or_else(overflow)?;
+        }
+        result = result.checked_add(&term).ok_or_else(overflow)?;
+    }
+    Ok(result)
+}
+
+// a polynomial found by detect_polynomial, kept as the leading finite differences of its samples
+// (Newton's forward difference form), so it's exact for whole numbers
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Polynomial<T> {
+    differences: Vec<T>,
+}
+
+impl<T: Int> Polynomial<T> {
+    pub fn degree(&self) -> usize {
+        self.differences.len().saturating_sub(1)
+    }
+
+    // the value at n, where the samples were taken at 0, 1, 2...
+    pub fn evaluate(&self, n: T) -> Result<T> {
+        let mut result = T::ZERO;
+        // n choose k, built up one k at a time so each step divides exactly
+        let mut choose = T::ONE;
+        let mut k = T::ZERO;
+        for &difference in &self.differences {
+            result = result.try_add(choose.try_mul(difference)?)?;
+            choose = match n.checked_sub(k) {
+                Some(rest) => choose.try_mul(rest)? / (k + T::ONE),
+                // an unsigned n less than k, where n choose k is 0 from here on
+                None => T::ZERO,
+            };
+            k = k + T::ONE;
+        }
+        Ok(result)
+    }
+}
+
+// the polynomial of the lowest degree, up to max_degree, through samples taken at 0, 1, 2... None
+// when the samples don't fit one, or when there are too few samples to tell: a row of differences
+// has to reach all zero to show the fit, which takes at least degree + 2 samples
+pub fn detect_polynomial<T: Int>(
+    samples: &[T],
+    max_degree: usize,
+) -> Result<Option<Polynomial<T>>> {
+    let table = difference_table(samples)?;
+    if table
+        .last()
+        .is_some_and(|row| row.iter().any(|&v| v != T::ZERO))
+    {
+        return Ok(None);
+    }
+    // every row before the zero row is a difference of the polynomial
+    let degree = table.len().saturating_sub(2);
+    if degree > max_degree {
+        return Ok(None);
+    }
+    Ok(Some(Polynomial {
+        differences: table.iter().take(degree + 1).map(|row| row[0]).collect(),
+    }))
 }
 
 pub trait RangeOperations {
@@ -114,4 +959,261 @@ mod tests {
         assert_eq!(vec![1..10], (1..10).difference(&(95..110)));
         assert_eq!(vec![91..100], (91..100).difference(&(1..10)));
     }
+
+    #[test]
+    fn generic_over_widths() -> Result<()> {
+        assert_eq!(12, least_common_multiple(&[4_usize, 6]));
+        assert_eq!(12, least_common_multiple(&[-4_i64, 6]));
+        assert_eq!(6, greatest_common_denominator(-12_i32, 18));
+
+        // coprime values near 2^40 have an lcm that only fits in 128 bits
+        let primes = [1_099_511_627_791_u128, 1_099_511_628_401];
+        assert_eq!(
+            1_208_925_820_318_316_616_492_191,
+            least_common_multiple(&primes)
+        );
+        assert!(
+            checked_least_common_multiple(&[1_099_511_627_791_u64, 1_099_511_628_401]).is_err()
+        );
+
+        let square = vec![
+            IPoint2D { x: 0, y: 0 },
+            IPoint2D { x: 0, y: 100_000 },
+            IPoint2D {
+                x: 100_000,
+                y: 100_000,
+            },
+            IPoint2D { x: 100_000, y: 0 },
+        ];
+        assert_eq!(10_000_000_000_i64, shoelace_loop_area(square.clone()));
+        assert!(checked_shoelace_loop_area::<i32>(square).is_err());
+
+        let err = i32::MAX.try_add(1).unwrap_err();
+        assert_eq!("2147483647 + 1 overflows i32", err.to_string());
+
+        Ok(())
+    }
+
+    #[test]
+    fn number_theory() -> Result<()> {
+        assert_eq!((2, -9, 47), extended_gcd(240_i32, 46));
+        assert_eq!((6, -1, 0), extended_gcd(-6_i64, 0));
+        assert_eq!(Some(4), mod_inverse(3_i64, 11));
+        assert_eq!(Some(7), mod_inverse(-3_i32, 11));
+        assert_eq!(None, mod_inverse(4_i32, 6));
+
+        assert_eq!(445, mod_pow(4_u32, 13, 497)?);
+        // 2^64 = 1 mod 2^64 - 1, and squaring anything that big overflows a u64
+        assert_eq!(1, mod_pow(2_u64, 64, u64::MAX)?);
+        // 3^-2 mod 11, through the inverse
+        assert_eq!(5, mod_pow(mod_inverse(3_i64, 11).unwrap(), 2, 11)?);
+        assert!(mod_pow(3_i64, -2, 11).is_err());
+        assert_eq!(u64::MAX - 1, mul_mod(u64::MAX - 1, 1, u64::MAX));
+
+        assert_eq!((23, 105), chinese_remainder(&[(2_i64, 3), (3, 5), (2, 7)])?);
+        // moduli that share a factor
+        assert_eq!((11, 12), chinese_remainder(&[(3_i32, 4), (5, 6)])?);
+        let err = chinese_remainder(&[(1_i32, 4), (2, 6)]).unwrap_err();
+        assert!(err.to_string().contains("have no solution"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn cycles_with_offsets() -> Result<()> {
+        assert_eq!(
+            (12, 12),
+            least_common_multiple_with_offsets(&[(4_i64, 4), (6, 6)])?
+        );
+        assert_eq!(
+            (8, 15),
+            least_common_multiple_with_offsets(&[(2_i64, 3), (3, 5)])?
+        );
+        // the first alignment can't be before a cycle starts
+        assert_eq!(
+            (23, 15),
+            least_common_multiple_with_offsets(&[(20_i64, 3), (3, 5)])?
+        );
+        assert!(least_common_multiple_with_offsets(&[(1_i64, 4), (2, 6)]).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn rational_arithmetic() -> Result<()> {
+        let r = |n: i64, d: i64| Rational::new(n, d).unwrap();
+
+        assert_eq!(r(1, 2), r(3, 6));
+        let third = r(2, -6);
+        assert_eq!((&-1, &3), (third.numerator(), third.denominator()));
+        assert_eq!(r(5, 6), r(1, 2) + r(1, 3));
+        assert_eq!(r(-1, 6), r(1, 6) - r(1, 3));
+        assert_eq!(r(1, 1), r(2, 3) * r(3, 2));
+        assert_eq!(r(4, 9), r(2, 3) / r(3, 2));
+        assert_eq!("-7/2", r(7, -2).to_string());
+        assert_eq!(Some(3), r(6, 2).to_integer());
+
+        assert!(r(1, 3) < r(1, 2));
+        assert!(r(-1, 2) < r(-1, 3));
+        assert!(r(7, 5) > r(4, 3));
+        // cross multiplying these would overflow
+        assert!(r(i64::MAX - 1, i64::MAX) > r(i64::MAX - 2, i64::MAX - 1));
+
+        assert!(Rational::new(1, 0).is_err());
+        assert_eq!(None, r(1, 2).checked_div(&r(0, 1)));
+        assert_eq!(None, r(i64::MAX, 1).checked_add(&r(1, 1)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn linear_systems() -> Result<()> {
+        let r = |n: i64| Rational::integer(n);
+
+        // x + y + z = 6, 2y + 5z = -4, 2x + 5y - z = 27
+        let a = vec![vec![1, 1, 1], vec![0, 2, 5], vec![2, 5, -1]];
+        let b = vec![6, -4, 27];
+        let expected = LinearSolution::Unique(vec![r(5), r(3), r(-2)]);
+        assert_eq!(expected, solve_integer_system(&a, &b)?);
+        let rational = |row: &Vec<i64>| row.iter().map(|&n| r(n)).collect::<Vec<_>>();
+        let a_rational: Vec<_> = a.iter().map(rational).collect();
+        assert_eq!(expected, solve_linear_system(&a_rational, &rational(&b))?);
+
+        // a fractional solution, with a zero that needs a row swap
+        let a = vec![vec![0, 2], vec![3, 1]];
+        let solution = LinearSolution::Unique(vec![Rational::new(1, 6)?, Rational::new(1, 2)?]);
+        assert_eq!(solution, solve_integer_system(&a, &[1, 1])?);
+
+        // parallel lines, and the same line twice
+        let a = vec![vec![1, 2], vec![2, 4]];
+        assert_eq!(
+            LinearSolution::NoSolution,
+            solve_integer_system(&a, &[3, 7])?
+        );
+        assert_eq!(
+            LinearSolution::InfinitelyMany,
+            solve_integer_system(&a, &[3, 6])?
+        );
+        let a_rational: Vec<_> = a.iter().map(rational).collect();
+        assert_eq!(
+            LinearSolution::NoSolution,
+            solve_linear_system(&a_rational, &[r(3), r(7)])?
+        );
+
+        // more equations than unknowns, as long as they agree
+        let a = vec![vec![1, 0], vec![0, 1], vec![1, 1]];
+        assert_eq!(
+            LinearSolution::Unique(vec![r(2), r(3)]),
+            solve_integer_system(&a, &[2, 3, 5])?
+        );
+
+        assert!(solve_integer_system(&[vec![1, 2]], &[1, 2]).is_err());
+        let err =
+            solve_integer_system(&[vec![i32::MAX, 1], vec![2, i32::MAX]], &[1, 1]).unwrap_err();
+        assert_eq!("overflow solving linear system", err.to_string());
+
+        Ok(())
+    }
+
+    #[test]
+    fn extrapolates_sequences() -> Result<()> {
+        assert_eq!(18, extrapolate_next(&[0, 3, 6, 9, 12, 15])?);
+        assert_eq!(68, extrapolate_next(&[10, 13, 16, 21, 30, 45])?);
+        assert_eq!(5, extrapolate_prev(&[10, 13, 16, 21, 30, 45])?);
+        assert_eq!(-3, extrapolate_prev(&[0_i64, 3, 6])?);
+        assert_eq!(7, extrapolate_next(&[7_u32])?);
+        assert!(extrapolate_next::<i32>(&[]).is_err());
+        assert!(extrapolate_next(&[i32::MAX - 1, i32::MAX]).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn interpolates_exactly() -> Result<()> {
+        assert_eq!(
+            Rational::integer(100),
+            lagrange_interpolate(&[(1_i64, 1), (2, 4), (3, 9)], 10)?
+        );
+        assert_eq!(
+            Rational::new(1, 2)?,
+            lagrange_interpolate(&[(0, 0), (2, 1)], 1)?
+        );
+        assert!(lagrange_interpolate(&[(1, 1), (1, 2)], 0).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn detects_polynomials() -> Result<()> {
+        // 3n^2 + 2n + 1
+        let samples: Vec<i64> = (0..6).map(|n| 3 * n * n + 2 * n + 1).collect();
+        let quadratic = detect_polynomial(&samples, 3)?.unwrap();
+        assert_eq!(2, quadratic.degree());
+        assert_eq!(
+            3 * 202300 * 202300 + 2 * 202300 + 1,
+            quadratic.evaluate(202300)?
+        );
+        assert_eq!(None, detect_polynomial(&samples, 1)?);
+
+        let constant = detect_polynomial(&[4, 4, 4], 0)?.unwrap();
+        assert_eq!((0, 4), (constant.degree(), constant.evaluate(1_000_000)?));
+        // not a polynomial at all
+        assert_eq!(None, detect_polynomial(&[1, 2, 4, 8, 16, 32], 3)?);
+        assert!(quadratic.evaluate(i64::MAX / 2).is_err());
+        // too few samples to check the fit, so nothing is detected
+        assert_eq!(None, detect_polynomial(&[1_u32, 6, 17], 2)?);
+        assert_eq!(None, detect_polynomial(&[1, 2, 4, 8, 16, 32], 5)?);
+        assert_eq!(
+            1,
+            detect_polynomial(&[1_u32, 6, 17, 34], 2)?
+                .unwrap()
+                .evaluate(0)?
+        );
+        assert_eq!(2, quadratic.evaluate(-1)?);
+
+        Ok(())
+    }
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn big_products() {
+        // a cross product of hailstone sized values
+        let position = BigInt::from(400_000_000_000_000_i64);
+        let product = &position * &position * BigInt::from(i128::MAX);
+        assert!(product > BigInt::from(i128::MAX));
+        assert_eq!(
+            position.clone() * position / BigInt::from(400_000_000_000_000_i64),
+            BigInt::from(400_000_000_000_000_i64)
+        );
+    }
+
+    #[test]
+    fn primes_and_divisors() {
+        assert_eq!(vec![2, 3, 5, 7, 11, 13, 17, 19], primes_up_to(20));
+        assert!(!prime_sieve(1)[1]);
+        assert_eq!(1, prime_sieve(0).len());
+
+        assert_eq!(vec![(2, 2), (3, 1), (7, 2)], factorize(588_i32));
+        assert_eq!(vec![(1_000_000_007, 1)], factorize(1_000_000_007_u64));
+        assert_eq!(vec![(2, 1), (3, 1)], factorize(-6_i64));
+        assert!(factorize(1_usize).is_empty());
+        // a prime near the top of the type, where p * p would overflow
+        assert_eq!(vec![(4_294_967_291, 1)], factorize(4_294_967_291_u32));
+
+        assert_eq!(vec![1, 2, 3, 4, 6, 12], divisors(12_u32));
+        assert_eq!(vec![1], divisors(1_i32));
+        assert_eq!(4, totient(12_i32));
+        assert_eq!(1_000_000_006, totient(1_000_000_007_i64));
+
+        let sums = divisor_sum_sieve(12, None);
+        assert_eq!(vec![0, 1, 3, 4, 7, 6, 12, 8, 15, 13, 18, 12, 28], sums);
+        for (n, sum) in sums.iter().enumerate().skip(1) {
+            assert_eq!(*sum, divisors(n).iter().sum::<usize>());
+        }
+        // 1, 2 and 3 stop before they get to 12
+        let capped = divisor_sum_sieve(12, Some(3));
+        assert_eq!(28 - 1 - 2 - 3, capped[12]);
+        assert_eq!(15 - 1 - 2, capped[8]);
+        assert_eq!(12 - 1, capped[6]);
+    }
 }
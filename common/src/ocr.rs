@@ -0,0 +1,344 @@
+// reads the block letters some puzzles draw instead of printing an answer. Both fonts are
+// supported, the 4x6 one most puzzles use and the 6x10 one from 2018 day 10:
+//
+//   .##..###.
+//   #..#.#..#
+//   #..#.###.   => "AB"
+//   ####.#..#
+//   #..#.#..#
+//   #..#.###.
+//
+// letters are split on blank columns, so the spacing between them doesn't matter
+use std::collections::HashSet;
+
+use anyhow::*;
+
+use crate::{Grid2D, Point2D, SparseGrid2D};
+
+const SMALL_FONT: [(char, [&str; 6]); 18] = [
+    ('A', [".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+    ('B', ["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+    ('C', [".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+    ('E', ["####", "#...", "###.", "#...", "#...", "####"]),
+    ('F', ["####", "#...", "###.", "#...", "#...", "#..."]),
+    ('G', [".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+    ('H', ["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+    ('I', [".###", "..#.", "..#.", "..#.", "..#.", ".###"]),
+    ('J', ["..##", "...#", "...#", "...#", "#..#", ".##."]),
+    ('K', ["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+    ('L', ["#...", "#...", "#...", "#...", "#...", "####"]),
+    ('O', [".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('P', ["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+    ('R', ["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+    ('S', [".###", "#...", "#...", ".##.", "...#", "###."]),
+    ('U', ["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('Y', ["#...#", "#...#", ".#.#.", "..#..", "..#..", "..#.."]),
+    ('Z', ["####", "...#", "..#.", ".#..", "#...", "####"]),
+];
+
+const LARGE_FONT: [(char, [&str; 10]); 15] = [
+    (
+        'A',
+        [
+            "..##..", ".#..#.", "#....#", "#....#", "#....#", "######", "#....#", "#....#",
+            "#....#", "#....#",
+        ],
+    ),
+    (
+        'B',
+        [
+            "#####.", "#....#", "#....#", "#....#", "#####.", "#....#", "#....#", "#....#",
+            "#....#", "#####.",
+        ],
+    ),
+    (
+        'C',
+        [
+            ".####.", "#....#", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....",
+            "#....#", ".####.",
+        ],
+    ),
+    (
+        'E',
+        [
+            "######", "#.....", "#.....", "#.....", "#####.", "#.....", "#.....", "#.....",
+            "#.....", "######",
+        ],
+    ),
+    (
+        'F',
+        [
+            "######", "#.....", "#.....", "#.....", "#####.", "#.....", "#.....", "#.....",
+            "#.....", "#.....",
+        ],
+    ),
+    (
+        'G',
+        [
+            ".####.", "#....#", "#.....", "#.....", "#.....", "#..###", "#....#", "#....#",
+            "#...##", ".###.#",
+        ],
+    ),
+    (
+        'H',
+        [
+            "#....#", "#....#", "#....#", "#....#", "######", "#....#", "#....#", "#....#",
+            "#....#", "#....#",
+        ],
+    ),
+    (
+        'J',
+        [
+            "...###", "....#.", "....#.", "....#.", "....#.", "....#.", "#...#.", "#...#.",
+            "#...#.", ".###..",
+        ],
+    ),
+    (
+        'K',
+        [
+            "#....#", "#...#.", "#..#..", "#.#...", "##....", "##....", "#.#...", "#..#..",
+            "#...#.", "#....#",
+        ],
+    ),
+    (
+        'L',
+        [
+            "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....",
+            "#.....", "######",
+        ],
+    ),
+    (
+        'N',
+        [
+            "#....#", "##...#", "##...#", "#.#..#", "#.#..#", "#..#.#", "#..#.#", "#...##",
+            "#...##", "#....#",
+        ],
+    ),
+    (
+        'P',
+        [
+            "#####.", "#....#", "#....#", "#....#", "#####.", "#.....", "#.....", "#.....",
+            "#.....", "#.....",
+        ],
+    ),
+    (
+        'R',
+        [
+            "#####.", "#....#", "#....#", "#....#", "#####.", "#..#..", "#...#.", "#...#.",
+            "#....#", "#....#",
+        ],
+    ),
+    (
+        'X',
+        [
+            "#....#", "#....#", ".#..#.", ".#..#.", "..##..", "..##..", ".#..#.", ".#..#.",
+            "#....#", "#....#",
+        ],
+    ),
+    (
+        'Z',
+        [
+            "######", ".....#", ".....#", "....#.", "...#..", "..#...", ".#....", "#.....",
+            "#.....", "######",
+        ],
+    ),
+];
+
+// anything that can be drawn as lit and unlit pixels
+pub trait Screen {
+    // one row per line, true where the pixel is lit
+    fn pixels(&self) -> Vec<Vec<bool>>;
+}
+
+// '#' and '█' are lit, anything else is not
+impl Screen for str {
+    fn pixels(&self) -> Vec<Vec<bool>> {
+        self.lines()
+            .map(|line| line.chars().map(|c| c == '#' || c == '█').collect())
+            .collect()
+    }
+}
+
+impl Screen for String {
+    fn pixels(&self) -> Vec<Vec<bool>> {
+        self.as_str().pixels()
+    }
+}
+
+impl Screen for Grid2D<bool> {
+    fn pixels(&self) -> Vec<Vec<bool>> {
+        self.data.clone()
+    }
+}
+
+// every point with a value is lit
+impl<T> Screen for SparseGrid2D<T> {
+    fn pixels(&self) -> Vec<Vec<bool>> {
+        points_to_pixels(self.points().copied().collect())
+    }
+}
+
+impl Screen for HashSet<Point2D> {
+    fn pixels(&self) -> Vec<Vec<bool>> {
+        points_to_pixels(self.iter().copied().collect())
+    }
+}
+
+fn points_to_pixels(points: Vec<Point2D>) -> Vec<Vec<bool>> {
+    let width = points.iter().map(|pt| pt.x + 1).max().unwrap_or(0);
+    let height = points.iter().map(|pt| pt.y + 1).max().unwrap_or(0);
+    let mut pixels = vec![vec![false; width]; height];
+    for pt in points {
+        pixels[pt.y][pt.x] = true;
+    }
+    pixels
+}
+
+// the letters drawn on screen
+pub fn ocr<S: Screen + ?Sized>(screen: &S) -> Result<String> {
+    let mut pixels = screen.pixels();
+    // blank rows above and below the letters don't matter
+    pixels.retain(|row| row.contains(&true));
+    let width = pixels.iter().map(Vec::len).max().unwrap_or(0);
+    let lit = |x: usize, y: usize| pixels[y].get(x).copied().unwrap_or(false);
+
+    let mut letters = String::new();
+    let mut x = 0;
+    while x < width {
+        if !(0..pixels.len()).any(|y| lit(x, y)) {
+            x += 1;
+            continue;
+        }
+        let start = x;
+        while x < width && (0..pixels.len()).any(|y| lit(x, y)) {
+            x += 1;
+        }
+        let glyph: Vec<Vec<bool>> = (0..pixels.len())
+            .map(|y| (start..x).map(|x| lit(x, y)).collect())
+            .collect();
+        letters.push(read_glyph(&glyph)?);
+    }
+
+    if letters.is_empty() {
+        bail!("no letters on screen");
+    }
+    Ok(letters)
+}
+
+fn read_glyph(glyph: &[Vec<bool>]) -> Result<char> {
+    let matches = |rows: &[&str]| {
+        let font = trimmed(rows);
+        font.len() == glyph.len() && font.iter().zip(glyph).all(|(font, row)| font == row)
+    };
+    let found = match glyph.len() {
+        6 => SMALL_FONT
+            .iter()
+            .find(|(_, rows)| matches(rows))
+            .map(|(letter, _)| *letter),
+        10 => LARGE_FONT
+            .iter()
+            .find(|(_, rows)| matches(rows))
+            .map(|(letter, _)| *letter),
+        height => bail!("letters should be 6 or 10 pixels tall, found {height}"),
+    };
+    found.ok_or_else(|| {
+        let drawing: Vec<String> = glyph
+            .iter()
+            .map(|row| row.iter().map(|&lit| if lit { '#' } else { '.' }).collect())
+            .collect();
+        anyhow!("unrecognized letter\n{}", drawing.join("\n"))
+    })
+}
+
+// font rows as pixels, without blank columns on either side
+fn trimmed(rows: &[&str]) -> Vec<Vec<bool>> {
+    let lit_column = |x: &usize| rows.iter().any(|row| row.as_bytes()[*x] == b'#');
+    let left = (0..rows[0].len()).find(lit_column).unwrap_or(0);
+    let right = (0..rows[0].len()).rfind(lit_column).unwrap_or(0);
+    rows.iter()
+        .map(|row| {
+            row.as_bytes()[left..=right]
+                .iter()
+                .map(|&b| b == b'#')
+                .collect()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pt;
+
+    #[test]
+    fn reads_small_font() -> Result<()> {
+        let screen = "\
+.##..###...##..####.
+#..#.#..#.#..#.#....
+#..#.###..#....###..
+####.#..#.#....#....
+#..#.#..#.#..#.#....
+#..#.###...##..####.
+";
+        assert_eq!("ABCE", ocr(screen)?);
+
+        // spacing and surrounding blank rows don't matter, and neither does I's narrow glyph
+        let screen = "\n..........\n.###..#..#\n..#...#..#\n..#...####\n..#...#..#\n..#...#..#\n.###..#..#\n";
+        assert_eq!("IH", ocr(screen)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn reads_large_font() -> Result<()> {
+        let screen = "\
+#....#..#....#
+#....#..#....#
+#....#...#..#.
+#....#...#..#.
+######....##..
+#....#....##..
+#....#...#..#.
+#....#...#..#.
+#....#..#....#
+#....#..#....#
+";
+        assert_eq!("HX", ocr(screen)?);
+
+        // a pixel off is an error, not a guess
+        assert!(ocr(&screen.replacen("######", "#####.", 1)).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn reads_grids_and_points() -> Result<()> {
+        let lit: HashSet<Point2D> = ["#...", "#...", "#...", "#...", "#...", "####"]
+            .iter()
+            .enumerate()
+            .flat_map(|(y, row)| {
+                row.char_indices()
+                    .filter(|(_, c)| *c == '#')
+                    .map(move |(x, _)| pt(x + 2, y + 1))
+            })
+            .collect();
+        assert_eq!("L", ocr(&lit)?);
+
+        let sparse: SparseGrid2D<()> = lit.iter().map(|&pt| (pt, ())).collect();
+        assert_eq!("L", ocr(&sparse)?);
+
+        let grid = Grid2D::new(lit.pixels());
+        assert_eq!("L", ocr(&grid)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn unknown_letters() {
+        let err = ocr("#.#\n.#.\n#.#\n.#.\n#.#\n.#.").unwrap_err();
+        assert!(err.to_string().starts_with("unrecognized letter\n#.#"));
+
+        let err = ocr("##\n##").unwrap_err();
+        assert!(err.to_string().contains("6 or 10 pixels tall"));
+    }
+}
@@ -1,115 +1,390 @@
 // parsing helpers
 use anyhow::*;
-use std::str::pattern::Pattern;
+use std::any::Any;
+use std::fmt;
+use std::ops::Range;
 
 pub fn trim(contents: &str) -> String {
     contents.trim().to_string()
 }
 
+// Structured parse failure. When a helper parses part of a larger input, the error is anchored to
+// the line of that input containing the problem, so nested FromStr impls that use these helpers
+// still report where in input.txt things went wrong. Returned wrapped in anyhow::Error, use
+// downcast_ref::<ParseError>() to inspect it
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    // 1-based, None when the input was a single line
+    pub line: Option<usize>,
+    // byte offsets into text
+    pub columns: Range<usize>,
+    // the line containing the problem
+    pub text: String,
+    // name of the type that failed to parse
+    pub target: String,
+    pub message: String,
+}
+
+impl ParseError {
+    pub fn new<T>(text: &str, message: impl fmt::Display) -> Self {
+        let text = text.lines().next().unwrap_or_default();
+        ParseError {
+            line: None,
+            columns: 0..text.len(),
+            text: text.to_string(),
+            target: short_type_name::<T>(),
+            message: message.to_string(),
+        }
+    }
+
+    fn from_err<T, TErr>(text: &str, err: TErr) -> Self
+    where
+        TErr: fmt::Display + 'static,
+    {
+        match nested_parse_error(&err) {
+            Some(nested) => nested,
+            None => ParseError::new::<T>(text, err),
+        }
+    }
+
+    // moves this error, which came from parsing part, to be relative to outer. When part isn't a
+    // slice of outer there's nothing to anchor to, and the error is left as it is
+    pub(crate) fn anchor(mut self, outer: &str, part: &str) -> Self {
+        let Some(offset) = offset_in(outer, part) else {
+            return self;
+        };
+        let inner = &outer[offset..];
+        let line_start_in_inner = match self.line {
+            Some(line) => inner
+                .split_inclusive('\n')
+                .take(line - 1)
+                .map(str::len)
+                .sum(),
+            None => 0,
+        };
+        let text_start = offset
+            + line_start_in_inner
+            + inner[line_start_in_inner..].find(&self.text).unwrap_or(0);
+
+        let line_start = outer[..text_start].rfind('\n').map_or(0, |ix| ix + 1);
+        let line_end = outer[text_start..]
+            .find('\n')
+            .map_or(outer.len(), |ix| text_start + ix);
+        let line = outer[line_start..line_end].trim_end_matches('\r');
+        let shift = text_start - line_start;
+
+        if self.line.is_some() || outer.contains('\n') {
+            self.line = Some(outer[..text_start].matches('\n').count() + 1);
+        }
+        self.columns = (self.columns.start + shift).min(line.len())
+            ..(self.columns.end + shift).min(line.len());
+        self.text = line.to_string();
+        self
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "failed to parse `{}`", self.target)?;
+        if let Some(line) = self.line {
+            write!(f, " at line {line}")?;
+        }
+        if !self.text.is_empty() {
+            let column = self.text[..self.columns.start].chars().count() + 1;
+            write!(f, ", column {column}")?;
+        }
+        write!(f, ": {}", self.message)?;
+
+        if !self.text.is_empty() {
+            let number = self.line.map(|line| line.to_string()).unwrap_or_default();
+            let gutter = " ".repeat(number.len());
+            let padding = self.text[..self.columns.start].chars().count();
+            let carets = self.text[self.columns.clone()].chars().count().max(1);
+            write!(
+                f,
+                "\n{gutter} |\n{number} | {}\n{gutter} | {}{}",
+                self.text,
+                " ".repeat(padding),
+                "^".repeat(carets)
+            )?;
+        }
+        std::result::Result::Ok(())
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+// when a FromStr impl uses these helpers itself, keep the innermost (most specific) error. Finding
+// it means downcasting through Any, which is why the helpers need FromStr errors to be 'static
+fn nested_parse_error<TErr>(err: &TErr) -> Option<ParseError>
+where
+    TErr: fmt::Display + 'static,
+{
+    let any: &dyn Any = err;
+    if let Some(err) = any.downcast_ref::<ParseError>() {
+        return Some(err.clone());
+    }
+    let err = any.downcast_ref::<Error>()?;
+    let mut nested = err.downcast_ref::<ParseError>()?.clone();
+    // keep any context that was added on top of the parse error
+    let outer = err.to_string();
+    if outer != nested.to_string() {
+        nested.message = format!("{outer}: {}", nested.message);
+    }
+    Some(nested)
+}
+
+// std::any::type_name, without module paths
+pub(crate) fn short_type_name<T>() -> String {
+    let name = std::any::type_name::<T>();
+    let mut short = String::with_capacity(name.len());
+    let mut segment = String::new();
+    for c in name.chars() {
+        if c.is_alphanumeric() || c == '_' {
+            segment.push(c);
+        } else if c == ':' {
+            segment.clear();
+        } else {
+            short.push_str(&segment);
+            segment.clear();
+            short.push(c);
+        }
+    }
+    short.push_str(&segment);
+    short
+}
+
+// where part starts in outer, or None when part isn't a slice of outer
+fn offset_in(outer: &str, part: &str) -> Option<usize> {
+    let outer_start = outer.as_ptr() as usize;
+    let start = part.as_ptr() as usize;
+    (start >= outer_start && start + part.len() <= outer_start + outer.len())
+        .then(|| start - outer_start)
+}
+
+// parses part, reporting any error relative to outer. part should be a slice of outer, errors from
+// parsing anything else aren't anchored
+pub fn parse_within<T>(outer: &str, part: &str) -> Result<T>
+where
+    T: std::str::FromStr,
+    <T as std::str::FromStr>::Err: std::fmt::Display + 'static,
+{
+    part.parse()
+        .map_err(|err| ParseError::from_err::<T, _>(part, err).anchor(outer, part))
+        .map_err(Error::from)
+}
+
+fn missing_part<T>(contents: &str, message: impl fmt::Display) -> Error {
+    let mut err = ParseError::new::<T>(contents, message);
+    err.columns = err.text.len()..err.text.len();
+    Error::from(err)
+}
+
 pub fn wrap_parse_error<T, TErr>(result: std::result::Result<T, TErr>) -> Result<T>
 where
-    TErr: std::fmt::Display,
+    TErr: std::fmt::Display + 'static,
 {
     match result {
         std::result::Result::Ok(value) => Ok(value),
-        std::result::Result::Err(err) => Err(anyhow!("parse error {}", err)),
+        std::result::Result::Err(err) => Err(ParseError::from_err::<T, _>("", err).into()),
     }
 }
 
 pub fn parse_all<T>(contents: &str) -> Result<T>
 where
     T: std::str::FromStr,
-    <T as std::str::FromStr>::Err: std::fmt::Display,
+    <T as std::str::FromStr>::Err: std::fmt::Display + 'static,
 {
-    wrap_parse_error(contents.trim().parse())
+    parse_within(contents, contents.trim())
 }
 
 pub fn parse_untrimmed<T>(contents: &str) -> Result<T>
 where
     T: std::str::FromStr,
-    <T as std::str::FromStr>::Err: std::fmt::Display,
+    <T as std::str::FromStr>::Err: std::fmt::Display + 'static,
 {
-    wrap_parse_error(contents.parse())
+    parse_within(contents, contents)
 }
 
 pub fn parse_lines<T>(contents: &str) -> Result<Vec<T>>
 where
     T: std::str::FromStr,
-    <T as std::str::FromStr>::Err: std::fmt::Display,
+    <T as std::str::FromStr>::Err: std::fmt::Display + 'static,
 {
     contents
         .lines()
-        .map(|x| wrap_parse_error(x.parse()))
+        .map(|x| parse_within(contents, x))
         .collect()
 }
 
+// what parse_split and friends split on. Works like std::str::pattern::Pattern, which is nightly
+// only, for char, &str, char slices and arrays, and FnMut(char) -> bool closures. With the
+// pattern feature, any Pattern works
+pub trait Separator {
+    // byte range of the first separator in haystack
+    fn find_in(&mut self, haystack: &str) -> Option<Range<usize>>;
+}
+
+#[cfg(not(feature = "pattern"))]
+mod separators {
+    use super::*;
+
+    impl Separator for char {
+        fn find_in(&mut self, haystack: &str) -> Option<Range<usize>> {
+            haystack.find(*self).map(|ix| ix..ix + self.len_utf8())
+        }
+    }
+
+    impl Separator for &str {
+        fn find_in(&mut self, haystack: &str) -> Option<Range<usize>> {
+            haystack.find(*self).map(|ix| ix..ix + self.len())
+        }
+    }
+
+    impl Separator for &String {
+        fn find_in(&mut self, haystack: &str) -> Option<Range<usize>> {
+            self.as_str().find_in(haystack)
+        }
+    }
+
+    impl Separator for &[char] {
+        fn find_in(&mut self, haystack: &str) -> Option<Range<usize>> {
+            (|c| self.contains(&c)).find_in(haystack)
+        }
+    }
+
+    impl<const N: usize> Separator for [char; N] {
+        fn find_in(&mut self, haystack: &str) -> Option<Range<usize>> {
+            self.as_slice().find_in(haystack)
+        }
+    }
+
+    impl<const N: usize> Separator for &[char; N] {
+        fn find_in(&mut self, haystack: &str) -> Option<Range<usize>> {
+            self.as_slice().find_in(haystack)
+        }
+    }
+
+    impl<F> Separator for F
+    where
+        F: FnMut(char) -> bool,
+    {
+        fn find_in(&mut self, haystack: &str) -> Option<Range<usize>> {
+            haystack
+                .char_indices()
+                .find(|&(_, c)| self(c))
+                .map(|(ix, c)| ix..ix + c.len_utf8())
+        }
+    }
+}
+
+#[cfg(feature = "pattern")]
+impl<P> Separator for P
+where
+    P: std::str::pattern::Pattern + Clone,
+{
+    fn find_in(&mut self, haystack: &str) -> Option<Range<usize>> {
+        haystack
+            .match_indices(self.clone())
+            .next()
+            .map(|(ix, found)| ix..ix + found.len())
+    }
+}
+
+// like str::split, for any Separator
+pub fn split_by<S: Separator>(haystack: &str, separator: S) -> SplitBy<'_, S> {
+    SplitBy {
+        rest: Some(haystack),
+        separator,
+    }
+}
+
+pub struct SplitBy<'a, S> {
+    rest: Option<&'a str>,
+    separator: S,
+}
+
+impl<'a, S: Separator> Iterator for SplitBy<'a, S> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        let rest = self.rest?;
+        match self.separator.find_in(rest) {
+            // an empty separator never splits, rather than looping forever
+            Some(found) if !found.is_empty() => {
+                self.rest = Some(&rest[found.end..]);
+                Some(&rest[..found.start])
+            }
+            _ => {
+                self.rest = None;
+                Some(rest)
+            }
+        }
+    }
+}
+
 pub fn parse_split<T, P>(input: &str, separator: P) -> Result<Vec<T>>
 where
     T: std::str::FromStr,
-    <T as std::str::FromStr>::Err: std::fmt::Display,
-    P: Pattern,
+    <T as std::str::FromStr>::Err: std::fmt::Display + 'static,
+    P: Separator,
 {
-    input
-        .split(separator)
-        .map(|x| wrap_parse_error(x.parse()))
+    split_by(input, separator)
+        .map(|x| parse_within(input, x))
         .collect()
 }
 
 pub fn parse_chars<T>(contents: &str) -> Result<Vec<T>>
 where
     T: std::str::FromStr,
-    <T as std::str::FromStr>::Err: std::fmt::Display,
+    <T as std::str::FromStr>::Err: std::fmt::Display + 'static,
 {
-    contents
-        .trim()
-        .chars()
-        .map(|x| wrap_parse_error(x.to_string().parse()))
-        .collect()
+    contents.trim().parse_chars()
 }
 
 pub fn parse_line_groups<T>(contents: &str) -> Result<Vec<T>>
 where
     T: std::str::FromStr,
-    <T as std::str::FromStr>::Err: std::fmt::Display,
+    <T as std::str::FromStr>::Err: std::fmt::Display + 'static,
 {
     contents
         .split("\n\n")
-        .map(|x| wrap_parse_error(x.parse()))
+        .map(|x| parse_within(contents, x))
         .collect()
 }
 
 pub fn parse_line_pairs<T>(contents: &str, separator: &str) -> Result<Vec<(T, T)>>
 where
     T: std::str::FromStr,
-    <T as std::str::FromStr>::Err: std::fmt::Display,
+    <T as std::str::FromStr>::Err: std::fmt::Display + 'static,
 {
     contents
         .lines()
-        .map(|x| wrap_parse_error(parse_pair(x, separator)))
+        .map(|x| anchor_result(contents, x, parse_pair(x, separator)))
         .collect()
 }
 
 pub fn parse_pair<T0, T1, P>(contents: &str, separator: P) -> Result<(T0, T1)>
 where
     T0: std::str::FromStr,
-    <T0 as std::str::FromStr>::Err: std::fmt::Display,
+    <T0 as std::str::FromStr>::Err: std::fmt::Display + 'static,
     T1: std::str::FromStr,
-    <T1 as std::str::FromStr>::Err: std::fmt::Display,
-    P: Pattern,
+    <T1 as std::str::FromStr>::Err: std::fmt::Display + 'static,
+    P: Separator,
 {
-    let mut parts = contents.split(separator);
+    let mut parts = split_by(contents, separator);
     Ok((
-        wrap_parse_error(
+        parse_within(
+            contents,
             parts
                 .next()
-                .ok_or_else(|| anyhow!("malformed pair"))?
-                .parse(),
+                .ok_or_else(|| missing_part::<(T0, T1)>(contents, "malformed pair"))?,
         )?,
-        wrap_parse_error(
+        parse_within(
+            contents,
             parts
                 .next()
-                .ok_or_else(|| anyhow!("malformed pair"))?
-                .parse(),
+                .ok_or_else(|| missing_part::<(T0, T1)>(contents, "malformed pair"))?,
         )?,
     ))
 }
@@ -125,46 +400,59 @@ where
     FParse1: Fn(&str) -> Result<T1>,
 {
     let mut parts = contents.split(separator);
+    let first = parts
+        .next()
+        .ok_or_else(|| missing_part::<(T0, T1)>(contents, "malformed pair"))?;
+    let second = parts
+        .next()
+        .ok_or_else(|| missing_part::<(T0, T1)>(contents, "malformed pair"))?;
     Ok((
-        parse0(parts.next().ok_or_else(|| anyhow!("malformed pair"))?)?,
-        parse1(parts.next().ok_or_else(|| anyhow!("malformed pair"))?)?,
+        anchor_result(contents, first, parse0(first))?,
+        anchor_result(contents, second, parse1(second))?,
     ))
 }
 
+// moves a ParseError from parsing part (a slice of outer) to be relative to outer
+fn anchor_result<T>(outer: &str, part: &str, result: Result<T>) -> Result<T> {
+    result.map_err(|err| match nested_parse_error(&err) {
+        Some(nested) => Error::from(nested.anchor(outer, part)),
+        None => err,
+    })
+}
+
 pub fn parse_triple<T0, T1, T2>(contents: &str, separator: &str) -> Result<(T0, T1, T2)>
 where
     T0: std::str::FromStr,
-    <T0 as std::str::FromStr>::Err: std::fmt::Display,
+    <T0 as std::str::FromStr>::Err: std::fmt::Display + 'static,
     T1: std::str::FromStr,
-    <T1 as std::str::FromStr>::Err: std::fmt::Display,
+    <T1 as std::str::FromStr>::Err: std::fmt::Display + 'static,
     T2: std::str::FromStr,
-    <T2 as std::str::FromStr>::Err: std::fmt::Display,
+    <T2 as std::str::FromStr>::Err: std::fmt::Display + 'static,
 {
     let mut parts = contents.split(separator);
+    let mut next = || {
+        parts
+            .next()
+            .ok_or_else(|| missing_part::<(T0, T1, T2)>(contents, "malformed triple"))
+    };
     Ok((
-        wrap_parse_error(
-            parts
-                .next()
-                .ok_or_else(|| anyhow!("malformed triple"))?
-                .parse(),
-        )?,
-        wrap_parse_error(
-            parts
-                .next()
-                .ok_or_else(|| anyhow!("malformed triple"))?
-                .parse(),
-        )?,
-        wrap_parse_error(
-            parts
-                .next()
-                .ok_or_else(|| anyhow!("malformed triple"))?
-                .parse(),
-        )?,
+        parse_within(contents, next()?)?,
+        parse_within(contents, next()?)?,
+        parse_within(contents, next()?)?,
     ))
 }
 
+fn grab_part<'a, T>(contents: &str, parts: &[&'a str], ix: usize) -> Result<&'a str> {
+    parts.get(ix).copied().ok_or_else(|| {
+        missing_part::<T>(
+            contents,
+            format!("malformed line, could not get index {ix}"),
+        )
+    })
+}
+
 // grabs the 2 items at ix0 and ix1, in a string separated by separator
-pub fn grab_2<T0, T1, P: Pattern>(
+pub fn grab_2<T0, T1, P: Separator>(
     contents: &str,
     separator: P,
     ix0: usize,
@@ -172,42 +460,14 @@ pub fn grab_2<T0, T1, P: Pattern>(
 ) -> Result<(T0, T1)>
 where
     T0: std::str::FromStr,
-    <T0 as std::str::FromStr>::Err: std::fmt::Display,
+    <T0 as std::str::FromStr>::Err: std::fmt::Display + 'static,
     T1: std::str::FromStr,
-    <T1 as std::str::FromStr>::Err: std::fmt::Display,
+    <T1 as std::str::FromStr>::Err: std::fmt::Display + 'static,
 {
-    let mut parts = contents.split(separator);
-    let mut ix = 0;
-    while ix < ix0 {
-        parts
-            .next()
-            .ok_or_else(|| anyhow!("malformed line, could not get index {}", ix0))?;
-
-        ix += 1;
-    }
-    let first = wrap_parse_error(
-        parts
-            .next()
-            .ok_or_else(|| anyhow!("malformed line, could not get index {}", ix0))?
-            .parse(),
-    )?;
-    ix += 1;
-    while ix < ix1 {
-        parts
-            .next()
-            .ok_or_else(|| anyhow!("malformed line, could not get index {}", ix1))?;
-
-        ix += 1;
-    }
-
+    let parts: Vec<&str> = split_by(contents, separator).collect();
     Ok((
-        first,
-        wrap_parse_error(
-            parts
-                .next()
-                .ok_or_else(|| anyhow!("malformed line, could not get index {}", ix1))?
-                .parse(),
-        )?,
+        parse_within(contents, grab_part::<T0>(contents, &parts, ix0)?)?,
+        parse_within(contents, grab_part::<T1>(contents, &parts, ix1)?)?,
     ))
 }
 
@@ -221,61 +481,172 @@ pub fn grab_3<T0, T1, T2>(
 ) -> Result<(T0, T1, T2)>
 where
     T0: std::str::FromStr,
-    <T0 as std::str::FromStr>::Err: std::fmt::Display,
+    <T0 as std::str::FromStr>::Err: std::fmt::Display + 'static,
     T1: std::str::FromStr,
-    <T1 as std::str::FromStr>::Err: std::fmt::Display,
+    <T1 as std::str::FromStr>::Err: std::fmt::Display + 'static,
     T2: std::str::FromStr,
-    <T2 as std::str::FromStr>::Err: std::fmt::Display,
+    <T2 as std::str::FromStr>::Err: std::fmt::Display + 'static,
 {
-    let mut parts = contents.split(separator);
-    let mut ix = 0;
-    while ix < ix0 {
-        parts
-            .next()
-            .ok_or_else(|| anyhow!("malformed line, could not get index {}", ix0))?;
+    let parts: Vec<&str> = contents.split(separator).collect();
+    Ok((
+        parse_within(contents, grab_part::<T0>(contents, &parts, ix0)?)?,
+        parse_within(contents, grab_part::<T1>(contents, &parts, ix1)?)?,
+        parse_within(contents, grab_part::<T2>(contents, &parts, ix2)?)?,
+    ))
+}
 
-        ix += 1;
+// byte ranges of each integer in contents. When signed, a '-' right before the digits makes the
+// number negative, unless it joins two words like "x-1" or "10-20"
+fn integer_spans(contents: &str, signed: bool) -> Vec<Range<usize>> {
+    let bytes = contents.as_bytes();
+    let mut spans = vec![];
+    let mut ix = 0;
+    while ix < bytes.len() {
+        if !bytes[ix].is_ascii_digit() {
+            ix += 1;
+            continue;
+        }
+        let mut start = ix;
+        while ix < bytes.len() && bytes[ix].is_ascii_digit() {
+            ix += 1;
+        }
+        if signed
+            && start > 0
+            && bytes[start - 1] == b'-'
+            && (start == 1 || !bytes[start - 2].is_ascii_alphanumeric())
+        {
+            start -= 1;
+        }
+        spans.push(start..ix);
     }
-    let first = wrap_parse_error(
-        parts
-            .next()
-            .ok_or_else(|| anyhow!("malformed line, could not get index {}", ix0))?
-            .parse(),
-    )?;
-    ix += 1;
-    while ix < ix1 {
-        parts
-            .next()
-            .ok_or_else(|| anyhow!("malformed line, could not get index {}", ix1))?;
+    spans
+}
 
-        ix += 1;
-    }
-    let second = wrap_parse_error(
-        parts
-            .next()
-            .ok_or_else(|| anyhow!("malformed line, could not get index {}", ix1))?
-            .parse(),
-    )?;
-    ix += 1;
+fn parse_spans<T>(contents: &str, signed: bool) -> Result<Vec<T>>
+where
+    T: std::str::FromStr,
+    <T as std::str::FromStr>::Err: std::fmt::Display + 'static,
+{
+    integer_spans(contents, signed)
+        .into_iter()
+        .map(|span| parse_within(contents, &contents[span]))
+        .collect()
+}
 
-    while ix < ix2 {
-        parts
-            .next()
-            .ok_or_else(|| anyhow!("malformed line, could not get index {}", ix2))?;
+// every integer in contents, ignoring any other text
+pub fn ints<T>(contents: &str) -> Result<Vec<T>>
+where
+    T: std::str::FromStr,
+    <T as std::str::FromStr>::Err: std::fmt::Display + 'static,
+{
+    parse_spans(contents, true)
+}
 
-        ix += 1;
-    }
+// every run of digits in contents, ignoring signs
+pub fn uints<T>(contents: &str) -> Result<Vec<T>>
+where
+    T: std::str::FromStr,
+    <T as std::str::FromStr>::Err: std::fmt::Display + 'static,
+{
+    parse_spans(contents, false)
+}
 
-    Ok((
-        first,
-        second,
-        wrap_parse_error(
-            parts
-                .next()
-                .ok_or_else(|| anyhow!("malformed line, could not get index {}", ix2))?
-                .parse(),
-        )?,
-    ))
+pub fn ints_by_line<T>(contents: &str) -> Result<Vec<Vec<T>>>
+where
+    T: std::str::FromStr,
+    <T as std::str::FromStr>::Err: std::fmt::Display + 'static,
+{
+    contents
+        .lines()
+        .map(|line| anchor_result(contents, line, ints(line)))
+        .collect()
+}
+
+// exactly N integers, e.g. let [x, y, dx, dy] = ints_n(line)?;
+pub fn ints_n<T, const N: usize>(contents: &str) -> Result<[T; N]>
+where
+    T: std::str::FromStr,
+    <T as std::str::FromStr>::Err: std::fmt::Display + 'static,
+{
+    let values: Vec<T> = ints(contents)?;
+    let found = values.len();
+    values.try_into().map_err(|_| {
+        Error::from(ParseError::new::<[T; N]>(
+            contents,
+            format!("expected {N} integers, found {found}"),
+        ))
+    })
+}
+
+// where the columns of an ascii-art drawing are
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ColumnLayout {
+    // the last line labels each column, like " 1   2   3 "
+    Footer,
+    // the first line labels each column
+    Header,
+    // a column every stride bytes, starting at offset
+    Stride { offset: usize, stride: usize },
+}
+
+// reads a drawing of stacked single character items, like
+//     [D]
+// [N] [C]
+// [Z] [M] [P]
+//  1   2   3
+// into the items of each column from bottom to top. Label lines put a column at the start of each
+// label. Blank cells are skipped, so lines may be ragged or missing trailing whitespace
+pub fn parse_column_stacks<T>(drawing: &str, layout: ColumnLayout) -> Result<Vec<Vec<T>>>
+where
+    T: std::str::FromStr,
+    <T as std::str::FromStr>::Err: std::fmt::Display + 'static,
+{
+    let mut rows: Vec<&str> = drawing
+        .lines()
+        .filter(|row| !row.trim().is_empty())
+        .collect();
+
+    let columns: Vec<usize> = match layout {
+        ColumnLayout::Footer | ColumnLayout::Header => {
+            let labels = match layout {
+                ColumnLayout::Footer => rows.pop(),
+                _ if rows.is_empty() => None,
+                _ => Some(rows.remove(0)),
+            }
+            .ok_or_else(|| missing_part::<Vec<Vec<T>>>(drawing, "missing column labels"))?;
+            labels
+                .char_indices()
+                .filter(|&(ix, c)| {
+                    !c.is_whitespace()
+                        && labels[..ix]
+                            .chars()
+                            .next_back()
+                            .is_none_or(char::is_whitespace)
+                })
+                .map(|(ix, _)| ix)
+                .collect()
+        }
+        ColumnLayout::Stride { offset, stride } => {
+            if stride == 0 {
+                bail!("column stride must be positive");
+            }
+            let width = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+            (offset..width).step_by(stride).collect()
+        }
+    };
+
+    let mut stacks: Vec<Vec<T>> = columns.iter().map(|_| vec![]).collect();
+    for row in rows.iter().rev() {
+        for (stack, &column) in stacks.iter_mut().zip(&columns) {
+            let Some(c) = row.get(column..).and_then(|rest| rest.chars().next()) else {
+                continue;
+            };
+            if !c.is_whitespace() {
+                stack.push(parse_within(drawing, &row[column..column + c.len_utf8()])?);
+            }
+        }
+    }
+    Ok(stacks)
 }
 
 pub trait Substring {
@@ -303,30 +674,30 @@ pub trait WrappedParsable<T> {
 impl<T> WrappedParsable<T> for str
 where
     T: std::str::FromStr,
-    <T as std::str::FromStr>::Err: std::fmt::Display,
+    <T as std::str::FromStr>::Err: std::fmt::Display + 'static,
 {
     fn parse_wrapped(&self) -> Result<T> {
-        wrap_parse_error(self.parse())
+        parse_within(self, self)
     }
 
     fn parse_lines(&self) -> Result<Vec<T>> {
-        self.lines().map(|line| line.parse_wrapped()).collect()
+        self.lines().map(|line| parse_within(self, line)).collect()
     }
 
     fn parse_split_whitespace(&self) -> Result<Vec<T>> {
         self.split_ascii_whitespace()
-            .map(|x| x.parse_wrapped())
+            .map(|x| parse_within(self, x))
             .collect()
     }
 
     fn parse_chars(&self) -> Result<Vec<T>> {
-        self.chars()
-            .map(|x| x.to_string().parse_wrapped())
+        self.char_indices()
+            .map(|(ix, c)| parse_within(self, &self[ix..ix + c.len_utf8()]))
             .collect()
     }
 
     fn parse_line_groups(&self) -> Result<Vec<T>> {
-        self.split("\n\n").map(|x| x.parse_wrapped()).collect()
+        self.split("\n\n").map(|x| parse_within(self, x)).collect()
     }
 }
 
@@ -339,25 +710,23 @@ pub trait WrappedPatternParsable<T, P> {
 impl<T, P> WrappedPatternParsable<T, P> for str
 where
     T: std::str::FromStr,
-    <T as std::str::FromStr>::Err: std::fmt::Display,
-    P: Pattern,
+    <T as std::str::FromStr>::Err: std::fmt::Display + 'static,
+    P: Separator,
 {
     fn parse_split(&self, separator: P) -> Result<Vec<T>> {
-        self.split(separator).map(|x| x.parse_wrapped()).collect()
+        split_by(self, separator)
+            .map(|x| parse_within(self, x))
+            .collect()
     }
 
     fn parse_split_trim(&self, separator: P) -> Result<Vec<T>> {
-        self.split(separator)
-            .map(|x| x.trim().parse_wrapped())
+        split_by(self, separator)
+            .map(|x| parse_within(self, x.trim()))
             .collect()
     }
 
     fn parse_pair(&self, separator: P) -> Result<(T, T)> {
-        let mut i = self.split(separator);
-        Ok((
-            i.next().ok_or_invalid()?.parse_wrapped()?,
-            i.next().ok_or_invalid()?.parse_wrapped()?,
-        ))
+        parse_pair(self, separator)
     }
 }
 
@@ -373,3 +742,200 @@ where
         self.ok_or_else(|| anyhow!("expected value, got none"))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_error<T>(result: Result<T>) -> ParseError {
+        match result {
+            Result::Ok(_) => panic!("expected a parse error"),
+            Err(err) => err.downcast_ref::<ParseError>().unwrap().clone(),
+        }
+    }
+
+    #[test]
+    fn line_errors_have_location() {
+        let err = parse_error(parse_lines::<u32>("1\n2\nx3\n4"));
+
+        assert_eq!(Some(3), err.line);
+        assert_eq!(0..2, err.columns);
+        assert_eq!("x3", err.text);
+        assert_eq!("u32", err.target);
+        assert_eq!(
+            "failed to parse `u32` at line 3, column 1: invalid digit found in string\n  |\n3 | x3\n  | ^^",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn unrelated_outer_input_is_ignored() {
+        let part = String::from("1\nx");
+        let err = parse_error::<u32>(parse_within("unrelated\ninput\nthat is longer", &part));
+        assert_eq!(None, err.line);
+        assert_eq!("1", err.text);
+    }
+
+    #[test]
+    fn split_errors_point_at_item() {
+        let err = parse_error::<Vec<i32>>("1,2,abc".parse_split(','));
+
+        assert_eq!(None, err.line);
+        assert_eq!(4..7, err.columns);
+        assert_eq!(
+            "failed to parse `i32`, column 5: invalid digit found in string\n |\n | 1,2,abc\n |     ^^^",
+            err.to_string()
+        );
+    }
+
+    struct Pair;
+
+    impl std::str::FromStr for Pair {
+        type Err = Error;
+
+        fn from_str(s: &str) -> Result<Self> {
+            let _: (u32, u32) = s.parse_pair(',').context("bad pair")?;
+            Ok(Pair)
+        }
+    }
+
+    #[test]
+    fn nested_errors_are_anchored_to_outer_input() {
+        let err = parse_error(parse_lines::<Pair>("1,2\n3,x"));
+        assert_eq!(Some(2), err.line);
+        assert_eq!(2..3, err.columns);
+        assert_eq!("3,x", err.text);
+        assert_eq!("u32", err.target);
+        assert_eq!("bad pair: invalid digit found in string", err.message);
+
+        let groups: Result<Vec<Vec<Pair>>> = "1,2\n3,4\n\n5,6\n7,8,\n9,y"
+            .split("\n\n")
+            .map(parse_lines)
+            .collect();
+        // no outer input to anchor to, so this is relative to the group
+        assert_eq!(Some(3), parse_error(groups).line);
+
+        let err = parse_error(parse_line_groups::<Wrapper>("1,2\n3,4\n\n5,6\n7,8\n9,y"));
+        assert_eq!(Some(6), err.line);
+        assert_eq!(2..3, err.columns);
+    }
+
+    struct Wrapper;
+
+    impl std::str::FromStr for Wrapper {
+        type Err = Error;
+
+        fn from_str(s: &str) -> Result<Self> {
+            let _: Vec<Pair> = parse_lines(s)?;
+            Ok(Wrapper)
+        }
+    }
+
+    #[test]
+    fn missing_items_point_past_the_end() {
+        let err = parse_error(parse_triple::<u8, u8, u8>("1 2", " "));
+        assert_eq!(3..3, err.columns);
+        assert_eq!("(u8, u8, u8)", err.target);
+
+        let err = parse_error(grab_2::<u8, u8, _>("a 1 2", ' ', 1, 5));
+        assert_eq!("malformed line, could not get index 5", err.message);
+    }
+
+    #[test]
+    fn extracts_integers() -> Result<()> {
+        let line = "Sensor at x=-2, y=15: closest beacon is at x=10, y=-16";
+        assert_eq!(vec![-2, 15, 10, -16], ints::<i32>(line)?);
+        assert_eq!(vec![2, 15, 10, 16], uints::<u32>(line)?);
+
+        // hyphens between words aren't signs
+        assert_eq!(vec![10, 20, 3], ints::<i64>("10-20 seed-to-soil x-3")?);
+        assert_eq!(vec![-5], ints::<i8>("-5")?);
+        assert_eq!(
+            vec![vec![1, 2], vec![], vec![-3]],
+            ints_by_line::<i32>("1 2\nnone\n(-3)")?
+        );
+
+        let [x, y, dx, dy] = ints_n::<i32, 4>("p=0,4 v=3,-3")?;
+        assert_eq!((0, 4, 3, -3), (x, y, dx, dy));
+
+        Ok(())
+    }
+
+    #[test]
+    fn integer_errors() {
+        let err = parse_error(ints_n::<i32, 3>("1, 2"));
+        assert_eq!("expected 3 integers, found 2", err.message);
+        assert_eq!("[i32; 3]", err.target);
+
+        let err = parse_error(ints_by_line::<u8>("1\nx=300"));
+        assert_eq!(Some(2), err.line);
+        assert_eq!(2..5, err.columns);
+    }
+
+    const CRATES: &str = "    [D]
+[N] [C]    
+[Z] [M] [P]
+ 1   2   3 ";
+
+    #[test]
+    fn column_stacks_from_labels() -> Result<()> {
+        let expected = vec![vec!['Z', 'N'], vec!['M', 'C', 'D'], vec!['P']];
+        assert_eq!(
+            expected,
+            parse_column_stacks::<char>(CRATES, ColumnLayout::Footer)?
+        );
+        assert_eq!(
+            expected,
+            parse_column_stacks::<char>(
+                CRATES.rsplit_once('\n').unwrap().0,
+                ColumnLayout::Stride {
+                    offset: 1,
+                    stride: 4
+                }
+            )?
+        );
+
+        let hanging = "a b  c\n1 2\n     3";
+        assert_eq!(
+            vec![vec![1], vec![2], vec![3]],
+            parse_column_stacks::<u8>(hanging, ColumnLayout::Header)?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn column_stack_errors() {
+        let err = parse_error(parse_column_stacks::<u8>("1 x\n1 2", ColumnLayout::Footer));
+        assert_eq!(Some(1), err.line);
+        assert_eq!(2..3, err.columns);
+
+        let err = parse_error(parse_column_stacks::<u8>("", ColumnLayout::Header));
+        assert_eq!("missing column labels", err.message);
+    }
+
+    #[test]
+    fn separators_split_like_str_split() {
+        let text = "a, b;c,,d";
+        let cases: Vec<(Vec<&str>, Vec<&str>)> = vec![
+            (split_by(text, ',').collect(), text.split(',').collect()),
+            (split_by(text, ", ").collect(), text.split(", ").collect()),
+            (
+                split_by(text, [',', ';']).collect(),
+                text.split([',', ';']).collect(),
+            ),
+            (
+                split_by(text, &[';', ' '][..]).collect(),
+                text.split(&[';', ' '][..]).collect(),
+            ),
+            (
+                split_by(text, |c: char| !c.is_alphabetic()).collect(),
+                text.split(|c: char| !c.is_alphabetic()).collect(),
+            ),
+            (split_by("", ',').collect(), "".split(',').collect()),
+        ];
+        for (split, expected) in cases {
+            assert_eq!(expected, split);
+        }
+    }
+}
@@ -0,0 +1,291 @@
+// scanf-style line parsing. A template is literal text with a `{}` placeholder for each capture,
+// `{{` and `}}` match literal braces:
+//
+//   let (op, x0, y0, x1, y1) = scan!("{} {},{} through {},{}", line => Op, usize, usize, usize, usize)?;
+//   let pos = scan!("p=<{},{}>", line => Point2D { x: usize, y: usize })?;
+//
+// Captures are matched lazily, but a capture only ends where its text parses as the requested type,
+// so "turn on 1,2 ..." still finds "turn on" as the Op above
+use anyhow::*;
+
+use crate::{parse_within, ParseError};
+
+// number of placeholders in template, used by scan! to check the type count at compile time
+pub const fn scan_placeholders(template: &str) -> usize {
+    let bytes = template.as_bytes();
+    let mut count = 0;
+    let mut ix = 0;
+    while ix < bytes.len() {
+        if ix + 1 < bytes.len()
+            && matches!(
+                (bytes[ix], bytes[ix + 1]),
+                (b'{', b'{') | (b'}', b'}') | (b'{', b'}')
+            )
+        {
+            if bytes[ix] == b'{' && bytes[ix + 1] == b'}' {
+                count += 1;
+            }
+            ix += 2;
+        } else {
+            ix += 1;
+        }
+    }
+    count
+}
+
+// literal text around each placeholder, always one more than the placeholder count
+fn template_literals(template: &str) -> Vec<String> {
+    let mut literals = vec![String::new()];
+    let mut chars = template.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, chars.peek()) {
+            ('{', Some('{')) | ('}', Some('}')) => {
+                chars.next();
+                literals.last_mut().unwrap().push(c);
+            }
+            ('{', Some('}')) => {
+                chars.next();
+                literals.push(String::new());
+            }
+            _ => literals.last_mut().unwrap().push(c),
+        }
+    }
+    literals
+}
+
+pub fn scan_accepts<T: std::str::FromStr>(part: &str) -> bool {
+    part.parse::<T>().is_ok()
+}
+
+struct TemplateMatch<'a, 'c> {
+    input: &'a str,
+    literals: Vec<String>,
+    accepts: &'c [fn(&str) -> bool],
+    captures: Vec<&'a str>,
+    // furthest position matched, for error reporting
+    furthest: usize,
+}
+
+impl<'a> TemplateMatch<'a, '_> {
+    fn capture_from(&mut self, pos: usize) -> bool {
+        self.furthest = self.furthest.max(pos);
+        let ix = self.captures.len();
+        let rest = &self.input[pos..];
+        if ix + 1 == self.literals.len() {
+            return rest.is_empty();
+        }
+
+        let literal = self.literals[ix + 1].as_str();
+        let literal_len = literal.len();
+        let ends: Vec<usize> = if ix + 2 == self.literals.len() {
+            // the last capture runs up to the trailing literal
+            rest.strip_suffix(literal)
+                .map(|capture| capture.len())
+                .into_iter()
+                .collect()
+        } else {
+            rest.match_indices(literal).map(|(end, _)| end).collect()
+        };
+
+        for end in ends.into_iter().filter(|&end| end > 0) {
+            let capture = &rest[..end];
+            if !self.accepts.get(ix).is_none_or(|accepts| accepts(capture)) {
+                continue;
+            }
+            self.captures.push(capture);
+            if self.capture_from(pos + end + literal_len) {
+                return true;
+            }
+            self.captures.pop();
+        }
+        false
+    }
+}
+
+// matches input against template, returning the text of each capture. accepts holds a check for
+// each capture's text, captures without one accept any non-empty text
+pub fn scan_template<'a, T>(
+    template: &str,
+    input: &'a str,
+    accepts: &[fn(&str) -> bool],
+) -> Result<Scanned<'a>> {
+    let literals = template_literals(template);
+    let mut matcher = TemplateMatch {
+        input,
+        accepts,
+        captures: Vec::with_capacity(literals.len() - 1),
+        furthest: 0,
+        literals,
+    };
+
+    let matched = match input.strip_prefix(matcher.literals[0].as_str()) {
+        Some(_) => matcher.capture_from(matcher.literals[0].len()),
+        None => false,
+    };
+    if !matched {
+        let mut err = ParseError::new::<T>(input, format!("does not match `{template}`"));
+        err.columns = matcher.furthest.min(err.text.len())..err.text.len();
+        bail!(err);
+    }
+
+    Ok(Scanned {
+        input,
+        captures: matcher.captures.into_iter(),
+    })
+}
+
+// the untyped version of scan!, returns the text of each capture
+pub fn scan_parts<'a>(template: &str, input: &'a str) -> Result<Vec<&'a str>> {
+    Ok(scan_template::<Vec<&str>>(template, input, &[])?
+        .captures
+        .collect())
+}
+
+pub struct Scanned<'a> {
+    input: &'a str,
+    captures: std::vec::IntoIter<&'a str>,
+}
+
+impl Scanned<'_> {
+    // parses the next capture
+    pub fn take<T>(&mut self) -> Result<T>
+    where
+        T: std::str::FromStr,
+        <T as std::str::FromStr>::Err: std::fmt::Display + 'static,
+    {
+        let capture = self
+            .captures
+            .next()
+            .ok_or_else(|| anyhow!("scan template has too few placeholders"))?;
+        parse_within(self.input, capture)
+    }
+}
+
+#[macro_export]
+macro_rules! scan {
+    ($template:literal, $input:expr => $name:ident { $($field:ident : $ty:ty),+ $(,)? }) => {{
+        const _: () = assert!(
+            $crate::scan_placeholders($template) == [$(stringify!($ty)),+].len(),
+            "scan! template placeholder count doesn't match the number of fields"
+        );
+        $crate::scan_template::<$name>(
+            $template,
+            ::core::convert::AsRef::<str>::as_ref(&$input),
+            &[$($crate::scan_accepts::<$ty>),+],
+        )
+        .and_then(|mut scanned| {
+            ::core::result::Result::Ok($name {
+                $($field: scanned.take::<$ty>()?),+
+            })
+        })
+    }};
+    ($template:literal, $input:expr => $ty:ty) => {{
+        const _: () = assert!(
+            $crate::scan_placeholders($template) == 1,
+            "scan! template placeholder count doesn't match the number of types"
+        );
+        $crate::scan_template::<$ty>(
+            $template,
+            ::core::convert::AsRef::<str>::as_ref(&$input),
+            &[$crate::scan_accepts::<$ty>],
+        )
+        .and_then(|mut scanned| scanned.take::<$ty>())
+    }};
+    ($template:literal, $input:expr => $($ty:ty),+ $(,)?) => {{
+        const _: () = assert!(
+            $crate::scan_placeholders($template) == [$(stringify!($ty)),+].len(),
+            "scan! template placeholder count doesn't match the number of types"
+        );
+        $crate::scan_template::<($($ty,)+)>(
+            $template,
+            ::core::convert::AsRef::<str>::as_ref(&$input),
+            &[$($crate::scan_accepts::<$ty>),+],
+        )
+        .and_then(|mut scanned| {
+            ::core::result::Result::Ok(($(scanned.take::<$ty>()?,)+))
+        })
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Point2D;
+
+    #[derive(Debug, PartialEq)]
+    enum Op {
+        TurnOn,
+        TurnOff,
+        Toggle,
+    }
+
+    impl std::str::FromStr for Op {
+        type Err = Error;
+
+        fn from_str(op: &str) -> Result<Self> {
+            match op {
+                "turn on" => Ok(Op::TurnOn),
+                "turn off" => Ok(Op::TurnOff),
+                "toggle" => Ok(Op::Toggle),
+                _ => bail!("unsupported op '{op}'"),
+            }
+        }
+    }
+
+    #[test]
+    fn counts_placeholders() {
+        assert_eq!(0, scan_placeholders("no captures"));
+        assert_eq!(2, scan_placeholders("{},{}"));
+        assert_eq!(1, scan_placeholders("{{{}}}"));
+        assert_eq!(0, scan_placeholders("{{}}"));
+    }
+
+    #[test]
+    fn captures_end_where_they_parse() -> Result<()> {
+        let (op, x0, y0, x1, y1) = scan!(
+            "{} {},{} through {},{}",
+            "turn off 499,499 through 500,500" => Op, usize, usize, usize, usize
+        )?;
+        assert_eq!(Op::TurnOff, op);
+        assert_eq!((499, 499, 500, 500), (x0, y0, x1, y1));
+
+        let (op, x0) = scan!("{} {},0 through 9,9", "toggle 3,0 through 9,9" => Op, usize)?;
+        assert_eq!((Op::Toggle, 3), (op, x0));
+
+        Ok(())
+    }
+
+    #[test]
+    fn binds_structs_and_single_values() -> Result<()> {
+        let pt = scan!("p=<{},{}>", String::from("p=<3,14>") => Point2D { x: usize, y: usize })?;
+        assert_eq!(Point2D { x: 3, y: 14 }, pt);
+
+        let id: u32 = scan!("Monkey {}:", "Monkey 7:" => u32)?;
+        assert_eq!(7, id);
+
+        let name = scan!("{{{}}}", "{abc}" => String)?;
+        assert_eq!("abc", name);
+
+        Ok(())
+    }
+
+    #[test]
+    fn untyped_parts() -> Result<()> {
+        assert_eq!(
+            vec!["px", "a<2006:qkq,m>2090:A,rfg"],
+            scan_parts("{}{{{}}}", "px{a<2006:qkq,m>2090:A,rfg}")?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn mismatch_points_at_furthest_match() {
+        let err = scan!("{} {},{} through {},{}", "turn on 1,2 thru 3,4" => Op, usize, usize, usize, usize)
+            .unwrap_err();
+        let err = err.downcast_ref::<ParseError>().unwrap();
+
+        assert_eq!("(Op, usize, usize, usize, usize)", err.target);
+        assert_eq!(10..20, err.columns);
+        assert!(err.message.contains("does not match"));
+    }
+}
@@ -0,0 +1,295 @@
+// parsing for inputs made of blank line separated sections, where each section holds something
+// different. Either destructure into a tuple, where a trailing Vec takes every remaining section:
+//
+//   let (seeds, maps): (Seeds, Vec<Map>) = parse_sections(contents)?;
+//
+// or pull sections out one at a time, by position or by their header line:
+//
+//   let mut sections = Sections::new(contents);
+//   let port: Port = sections.take()?;
+//   let moves: Vec<Move> = sections.take_with(parse_lines)?;
+//   let soil: Map = sections.named("seed-to-soil map")?;
+//
+// errors are anchored to the line of the whole input, and say which section they came from
+use anyhow::*;
+
+use crate::{parse_within, ParseError};
+
+pub struct Sections<'a> {
+    contents: &'a str,
+    sections: Vec<&'a str>,
+    next: usize,
+}
+
+impl<'a> Sections<'a> {
+    pub fn new(contents: &'a str) -> Self {
+        Sections {
+            contents,
+            sections: contents
+                .split("\n\n")
+                .filter(|section| !section.trim().is_empty())
+                .collect(),
+            next: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.sections.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sections.is_empty()
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.sections.len() - self.next
+    }
+
+    // parses the next section
+    pub fn take<T>(&mut self) -> Result<T>
+    where
+        T: std::str::FromStr,
+        <T as std::str::FromStr>::Err: std::fmt::Display + 'static,
+    {
+        self.take_with(|section| parse_within(section, section))
+    }
+
+    // parses the next section with parse, for sections that aren't a single FromStr
+    pub fn take_with<T>(&mut self, parse: impl FnOnce(&'a str) -> Result<T>) -> Result<T> {
+        let ix = self.next;
+        let section = *self.sections.get(ix).ok_or_else(|| {
+            let mut err = ParseError::new::<T>(
+                "",
+                format!("expected section {}, found {}", ix + 1, self.len()),
+            );
+            err.line = Some(self.contents.lines().count().max(1));
+            Error::from(err)
+        })?;
+        self.next += 1;
+        self.parse_section(ix, section, parse)
+    }
+
+    // parses every remaining section
+    pub fn take_rest<T>(&mut self) -> Result<Vec<T>>
+    where
+        T: std::str::FromStr,
+        <T as std::str::FromStr>::Err: std::fmt::Display + 'static,
+    {
+        (self.next..self.len()).map(|_| self.take()).collect()
+    }
+
+    // parses the section whose first line is header (with or without a trailing ':'), without
+    // the header line. Doesn't move the position used by take
+    pub fn named<T>(&self, header: &str) -> Result<T>
+    where
+        T: std::str::FromStr,
+        <T as std::str::FromStr>::Err: std::fmt::Display + 'static,
+    {
+        self.named_with(header, |body| parse_within(body, body))
+    }
+
+    pub fn named_with<T>(
+        &self,
+        header: &str,
+        parse: impl FnOnce(&'a str) -> Result<T>,
+    ) -> Result<T> {
+        let (ix, body) = self
+            .sections
+            .iter()
+            .enumerate()
+            .find_map(|(ix, section)| {
+                let (first, body) = section.split_once('\n').unwrap_or((section, ""));
+                let first = first.trim_end();
+                (first == header || first.strip_suffix(':') == Some(header)).then_some((ix, body))
+            })
+            .ok_or_else(|| anyhow!("missing section `{header}`"))?;
+        self.parse_section(ix, body, parse)
+    }
+
+    // errors if any sections haven't been taken
+    pub fn finish(&self) -> Result<()> {
+        match self.sections.get(self.next) {
+            Some(section) => Err(self.anchor(
+                self.next,
+                section,
+                Error::from(ParseError::new::<Self>(
+                    section,
+                    format!("{} unexpected section(s)", self.remaining()),
+                )),
+            )),
+            None => Ok(()),
+        }
+    }
+
+    fn parse_section<T>(
+        &self,
+        ix: usize,
+        section: &'a str,
+        parse: impl FnOnce(&'a str) -> Result<T>,
+    ) -> Result<T> {
+        parse(section).map_err(|err| self.anchor(ix, section, err))
+    }
+
+    fn anchor(&self, ix: usize, section: &str, err: Error) -> Error {
+        let header = self.sections[ix].lines().next().unwrap_or_default();
+        let context = format!("in section {} `{header}`", ix + 1);
+        match err.downcast_ref::<ParseError>() {
+            Some(nested) => {
+                let mut nested = nested.clone();
+                nested.message = format!("{context}: {}", nested.message);
+                Error::from(nested.anchor(self.contents, section))
+            }
+            None => err.context(context),
+        }
+    }
+}
+
+// how a tuple element is pulled from Sections. The marker type keeps the FromStr and Vec impls
+// from overlapping
+pub trait Section<Marker>: Sized {
+    fn take_from(sections: &mut Sections) -> Result<Self>;
+}
+
+pub struct OneSection;
+pub struct EverySection;
+
+impl<T> Section<OneSection> for T
+where
+    T: std::str::FromStr,
+    <T as std::str::FromStr>::Err: std::fmt::Display + 'static,
+{
+    fn take_from(sections: &mut Sections) -> Result<Self> {
+        sections.take()
+    }
+}
+
+impl<T> Section<EverySection> for Vec<T>
+where
+    T: std::str::FromStr,
+    <T as std::str::FromStr>::Err: std::fmt::Display + 'static,
+{
+    fn take_from(sections: &mut Sections) -> Result<Self> {
+        sections.take_rest()
+    }
+}
+
+pub trait FromSections<Marker>: Sized {
+    fn from_sections(sections: &mut Sections) -> Result<Self>;
+}
+
+macro_rules! tuple_from_sections {
+    ($($t:ident $m:ident),+) => {
+        impl<$($t, $m),+> FromSections<($($m,)+)> for ($($t,)+)
+        where
+            $($t: Section<$m>),+
+        {
+            fn from_sections(sections: &mut Sections) -> Result<Self> {
+                Ok(($($t::take_from(sections)?,)+))
+            }
+        }
+    };
+}
+
+tuple_from_sections!(T0 M0, T1 M1);
+tuple_from_sections!(T0 M0, T1 M1, T2 M2);
+tuple_from_sections!(T0 M0, T1 M1, T2 M2, T3 M3);
+tuple_from_sections!(T0 M0, T1 M1, T2 M2, T3 M3, T4 M4);
+tuple_from_sections!(T0 M0, T1 M1, T2 M2, T3 M3, T4 M4, T5 M5);
+tuple_from_sections!(T0 M0, T1 M1, T2 M2, T3 M3, T4 M4, T5 M5, T6 M6);
+tuple_from_sections!(T0 M0, T1 M1, T2 M2, T3 M3, T4 M4, T5 M5, T6 M6, T7 M7);
+
+// splits contents into sections and parses each into the matching tuple element. Every section
+// must be used
+pub fn parse_sections<T, Marker>(contents: &str) -> Result<T>
+where
+    T: FromSections<Marker>,
+{
+    let mut sections = Sections::new(contents);
+    let parsed = T::from_sections(&mut sections)?;
+    sections.finish()?;
+    Ok(parsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_lines, uints};
+
+    struct Map {
+        name: String,
+        ranges: Vec<String>,
+    }
+
+    impl std::str::FromStr for Map {
+        type Err = Error;
+
+        fn from_str(map: &str) -> Result<Self> {
+            let (name, ranges) = map
+                .split_once(" map:\n")
+                .ok_or_else(|| anyhow!("no header"))?;
+            Ok(Map {
+                name: name.to_string(),
+                ranges: parse_lines(ranges)?,
+            })
+        }
+    }
+
+    const SAMPLE: &str = "\
+seeds: 79 14
+
+seed-to-soil map:
+50 98 2
+52 50 48
+
+soil-to-fertilizer map:
+0 15 37
+";
+
+    #[test]
+    fn tuple_with_trailing_vec() -> Result<()> {
+        let (seeds, maps): (String, Vec<Map>) = parse_sections(SAMPLE)?;
+
+        assert_eq!("seeds: 79 14", seeds);
+        assert_eq!(2, maps.len());
+        assert_eq!("soil-to-fertilizer", maps[1].name);
+        assert_eq!(vec!["0 15 37"], maps[1].ranges);
+
+        let (_, soil, fertilizer): (String, Map, Map) = parse_sections(SAMPLE)?;
+        assert_eq!("seed-to-soil", soil.name);
+        assert_eq!("soil-to-fertilizer", fertilizer.name);
+
+        Ok(())
+    }
+
+    #[test]
+    fn positional_and_named() -> Result<()> {
+        let mut sections = Sections::new(SAMPLE);
+        assert_eq!(3, sections.len());
+
+        let seeds: Vec<u32> = sections.take_with(uints)?;
+        assert_eq!(vec![79, 14], seeds);
+        assert_eq!(2, sections.remaining());
+
+        let fertilizer: Vec<String> = sections.named_with("soil-to-fertilizer map", parse_lines)?;
+        assert_eq!(vec!["0 15 37"], fertilizer);
+        let soil: Vec<String> = sections.named_with("seed-to-soil map:", parse_lines)?;
+        assert_eq!(2, soil.len());
+        assert!(sections.named::<String>("humidity map").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn errors_name_the_section() {
+        let err = parse_sections::<(String, Vec<u32>), _>("a\n\n1\n\nx").unwrap_err();
+        let err = err.downcast_ref::<ParseError>().unwrap();
+        assert_eq!(Some(5), err.line);
+        assert!(err.message.starts_with("in section 3 `x`: "));
+
+        let err = parse_sections::<(String, String), _>("a\n\nb\n\nc").unwrap_err();
+        assert!(err.to_string().contains("1 unexpected section(s)"));
+
+        let err = parse_sections::<(String, String, String), _>("a\n\nb").unwrap_err();
+        assert!(err.to_string().contains("expected section 3, found 2"));
+    }
+}
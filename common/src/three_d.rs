@@ -1,12 +1,13 @@
 use std::cmp::{max, min};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::fmt;
-use std::ops::Range;
+use std::ops::{Add, Index, Mul, Neg, Range, Sub};
 use std::str::FromStr;
 
 use anyhow::*;
 use itertools::Itertools;
 
-use crate::WrappedPatternParsable;
+use crate::{RangeOperations, WrappedPatternParsable};
 
 #[derive(Copy, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
 pub struct Point3D {
@@ -41,10 +42,6 @@ impl Point3D {
             z: self.z - value,
         }
     }
-
-    pub fn to_f64_vec(&self) -> Vec<f64> {
-        vec![self.x as f64, self.y as f64, self.z as f64]
-    }
 }
 
 impl FromStr for Point3D {
@@ -188,12 +185,6 @@ pub struct IVector3D {
     pub dz: i64,
 }
 
-impl IVector3D {
-    pub fn to_f64_vec(&self) -> Vec<f64> {
-        vec![self.dx as f64, self.dy as f64, self.dz as f64]
-    }
-}
-
 impl FromStr for IVector3D {
     type Err = Error;
 
@@ -229,3 +220,812 @@ impl TryFrom<Vec<i64>> for IVector3D {
         })
     }
 }
+
+// signed points, for puzzles where 3D coordinates can go negative
+#[derive(Copy, Clone, Default, PartialEq, Eq, Hash, Ord, PartialOrd)]
+pub struct IPoint3D {
+    pub x: i64,
+    pub y: i64,
+    pub z: i64,
+}
+
+impl IPoint3D {
+    pub const ORIGIN: IPoint3D = IPoint3D { x: 0, y: 0, z: 0 };
+
+    pub fn manhattan_distance(&self, other: IPoint3D) -> i64 {
+        (other.x - self.x).abs() + (other.y - self.y).abs() + (other.z - self.z).abs()
+    }
+
+    // the six points sharing a face with this one
+    pub fn face_neighbors(&self) -> [IPoint3D; 6] {
+        [
+            ipt3(self.x - 1, self.y, self.z),
+            ipt3(self.x + 1, self.y, self.z),
+            ipt3(self.x, self.y - 1, self.z),
+            ipt3(self.x, self.y + 1, self.z),
+            ipt3(self.x, self.y, self.z - 1),
+            ipt3(self.x, self.y, self.z + 1),
+        ]
+    }
+
+    // the 26 points sharing a face, edge or corner with this one
+    pub fn all_neighbors(&self) -> impl Iterator<Item = IPoint3D> + '_ {
+        (-1..=1)
+            .cartesian_product(-1..=1)
+            .cartesian_product(-1..=1)
+            .filter(|&((dx, dy), dz)| (dx, dy, dz) != (0, 0, 0))
+            .map(|((dx, dy), dz)| ipt3(self.x + dx, self.y + dy, self.z + dz))
+    }
+}
+
+impl Add for IPoint3D {
+    type Output = IPoint3D;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        ipt3(self.x + rhs.x, self.y + rhs.y, self.z + rhs.z)
+    }
+}
+
+impl Sub for IPoint3D {
+    type Output = IPoint3D;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        ipt3(self.x - rhs.x, self.y - rhs.y, self.z - rhs.z)
+    }
+}
+
+impl Neg for IPoint3D {
+    type Output = IPoint3D;
+
+    fn neg(self) -> Self::Output {
+        ipt3(-self.x, -self.y, -self.z)
+    }
+}
+
+impl Mul<i64> for IPoint3D {
+    type Output = IPoint3D;
+
+    fn mul(self, rhs: i64) -> Self::Output {
+        ipt3(self.x * rhs, self.y * rhs, self.z * rhs)
+    }
+}
+
+impl FromStr for IPoint3D {
+    type Err = Error;
+
+    fn from_str(point: &str) -> Result<Self> {
+        let parsed: Vec<i64> = point.parse_split(',')?;
+        parsed.try_into()
+    }
+}
+
+impl fmt::Display for IPoint3D {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{},{},{}", self.x, self.y, self.z)
+    }
+}
+
+impl fmt::Debug for IPoint3D {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+impl TryFrom<Vec<i64>> for IPoint3D {
+    type Error = Error;
+
+    fn try_from(value: Vec<i64>) -> std::prelude::v1::Result<Self, Self::Error> {
+        if value.len() != 3 {
+            bail!("invalid value, must have len 3");
+        }
+        Ok(ipt3(value[0], value[1], value[2]))
+    }
+}
+
+#[inline]
+pub fn ipt3(x: i64, y: i64, z: i64) -> IPoint3D {
+    IPoint3D { x, y, z }
+}
+
+// one of the 24 ways to turn something so its axes still line up with x, y and z. Each row of the
+// matrix has a single 1 or -1 in it
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Ord, PartialOrd)]
+pub struct Rotation3D {
+    matrix: [[i64; 3]; 3],
+}
+
+impl Rotation3D {
+    pub const IDENTITY: Rotation3D = Rotation3D {
+        matrix: [[1, 0, 0], [0, 1, 0], [0, 0, 1]],
+    };
+
+    // every axis permutation and choice of signs that doesn't mirror the axes
+    pub fn all() -> Vec<Rotation3D> {
+        let permutations = [
+            [0, 1, 2],
+            [0, 2, 1],
+            [1, 0, 2],
+            [1, 2, 0],
+            [2, 0, 1],
+            [2, 1, 0],
+        ];
+        let mut rotations = vec![];
+        for axes in permutations {
+            for signs in 0..8 {
+                let mut matrix = [[0; 3]; 3];
+                for (row, axis) in axes.iter().enumerate() {
+                    matrix[row][*axis] = if signs & (1 << row) == 0 { 1 } else { -1 };
+                }
+                let rotation = Rotation3D { matrix };
+                if rotation.determinant() == 1 {
+                    rotations.push(rotation);
+                }
+            }
+        }
+        rotations
+    }
+
+    fn determinant(&self) -> i64 {
+        let m = &self.matrix;
+        m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+            - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+            + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+    }
+
+    pub fn apply(&self, pt: IPoint3D) -> IPoint3D {
+        let m = &self.matrix;
+        ipt3(
+            m[0][0] * pt.x + m[0][1] * pt.y + m[0][2] * pt.z,
+            m[1][0] * pt.x + m[1][1] * pt.y + m[1][2] * pt.z,
+            m[2][0] * pt.x + m[2][1] * pt.y + m[2][2] * pt.z,
+        )
+    }
+
+    // this rotation followed by next
+    pub fn then(&self, next: &Rotation3D) -> Rotation3D {
+        let mut matrix = [[0; 3]; 3];
+        for (row, values) in matrix.iter_mut().enumerate() {
+            for (col, value) in values.iter_mut().enumerate() {
+                *value = (0..3)
+                    .map(|k| next.matrix[row][k] * self.matrix[k][col])
+                    .sum();
+            }
+        }
+        Rotation3D { matrix }
+    }
+
+    // rotation matrices are orthogonal, so the inverse is the transpose
+    pub fn inverse(&self) -> Rotation3D {
+        let mut matrix = [[0; 3]; 3];
+        for (row, values) in matrix.iter_mut().enumerate() {
+            for (col, value) in values.iter_mut().enumerate() {
+                *value = self.matrix[col][row];
+            }
+        }
+        Rotation3D { matrix }
+    }
+}
+
+impl Default for Rotation3D {
+    fn default() -> Self {
+        Rotation3D::IDENTITY
+    }
+}
+
+impl Mul<IPoint3D> for Rotation3D {
+    type Output = IPoint3D;
+
+    fn mul(self, rhs: IPoint3D) -> Self::Output {
+        self.apply(rhs)
+    }
+}
+
+// moves points from one frame of reference into another: rotate, then translate
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Alignment3D {
+    pub rotation: Rotation3D,
+    pub translation: IPoint3D,
+}
+
+impl Alignment3D {
+    pub fn apply(&self, pt: IPoint3D) -> IPoint3D {
+        self.rotation.apply(pt) + self.translation
+    }
+}
+
+// finds the rotation and translation that lines up at least min_matches of points with
+// reference_points, like the beacons two scanners can both see in 2021 day 19. The translation is
+// where the origin of points ends up in the reference frame
+pub fn align_points(
+    reference_points: &[IPoint3D],
+    points: &[IPoint3D],
+    min_matches: usize,
+) -> Option<Alignment3D> {
+    for rotation in Rotation3D::all() {
+        let rotated: Vec<IPoint3D> = points.iter().map(|pt| rotation.apply(*pt)).collect();
+        // every pair of points votes for the translation that would line them up
+        let mut votes: HashMap<IPoint3D, usize> = HashMap::new();
+        for reference in reference_points {
+            for pt in &rotated {
+                let count = votes.entry(*reference - *pt).or_default();
+                *count += 1;
+                if *count >= min_matches {
+                    return Some(Alignment3D {
+                        rotation,
+                        translation: *reference - *pt,
+                    });
+                }
+            }
+        }
+    }
+    None
+}
+
+// a box of signed points, including both corners. Unlike Cuboid, origin is always the minimum
+// corner, so origin <= terminex on every axis
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Ord, PartialOrd)]
+pub struct ICuboid {
+    pub origin: IPoint3D,
+    pub terminex: IPoint3D,
+}
+
+impl ICuboid {
+    pub fn new(a: IPoint3D, b: IPoint3D) -> ICuboid {
+        ICuboid {
+            origin: ipt3(min(a.x, b.x), min(a.y, b.y), min(a.z, b.z)),
+            terminex: ipt3(max(a.x, b.x), max(a.y, b.y), max(a.z, b.z)),
+        }
+    }
+
+    pub fn x_range(&self) -> Range<i64> {
+        self.origin.x..(self.terminex.x + 1)
+    }
+
+    pub fn y_range(&self) -> Range<i64> {
+        self.origin.y..(self.terminex.y + 1)
+    }
+
+    pub fn z_range(&self) -> Range<i64> {
+        self.origin.z..(self.terminex.z + 1)
+    }
+
+    // the number of points inside
+    pub fn volume(&self) -> i64 {
+        let size = self.terminex - self.origin + ipt3(1, 1, 1);
+        size.x * size.y * size.z
+    }
+
+    pub fn contains(&self, pt: &IPoint3D) -> bool {
+        (self.origin.x..=self.terminex.x).contains(&pt.x)
+            && (self.origin.y..=self.terminex.y).contains(&pt.y)
+            && (self.origin.z..=self.terminex.z).contains(&pt.z)
+    }
+
+    fn from_ranges(x: Range<i64>, y: Range<i64>, z: Range<i64>) -> ICuboid {
+        ICuboid {
+            origin: ipt3(x.start, y.start, z.start),
+            terminex: ipt3(x.end - 1, y.end - 1, z.end - 1),
+        }
+    }
+}
+
+impl From<(IPoint3D, IPoint3D)> for ICuboid {
+    fn from((a, b): (IPoint3D, IPoint3D)) -> Self {
+        ICuboid::new(a, b)
+    }
+}
+
+// works axis by axis using the range versions
+impl RangeOperations for ICuboid {
+    type RangeOutput = ICuboid;
+    type Index = IPoint3D;
+
+    fn overlaps(&self, other: &Self) -> bool {
+        self.x_range().overlaps(&other.x_range())
+            && self.y_range().overlaps(&other.y_range())
+            && self.z_range().overlaps(&other.z_range())
+    }
+
+    fn intersect(&self, other: &Self) -> Option<Self::RangeOutput> {
+        Some(ICuboid::from_ranges(
+            self.x_range().intersect(&other.x_range())?,
+            self.y_range().intersect(&other.y_range())?,
+            self.z_range().intersect(&other.z_range())?,
+        ))
+    }
+
+    fn offset(&self, delta: IPoint3D) -> Self::RangeOutput {
+        ICuboid {
+            origin: self.origin + delta,
+            terminex: self.terminex + delta,
+        }
+    }
+
+    // the part of self outside other, as at most 6 disjoint cuboids: whole slabs on either side in
+    // x, then the y slabs within other's x range, then the z slabs within both
+    fn difference(&self, other: &Self) -> Vec<Self::RangeOutput> {
+        let Some(overlap) = self.intersect(other) else {
+            return vec![*self];
+        };
+        let (x, y, z) = (self.x_range(), self.y_range(), self.z_range());
+        let (inner_x, inner_y) = (overlap.x_range(), overlap.y_range());
+        let mut pieces = vec![];
+        for outer_x in x.difference(&inner_x) {
+            pieces.push(ICuboid::from_ranges(outer_x, y.clone(), z.clone()));
+        }
+        for outer_y in y.difference(&inner_y) {
+            pieces.push(ICuboid::from_ranges(inner_x.clone(), outer_y, z.clone()));
+        }
+        for outer_z in z.difference(&overlap.z_range()) {
+            pieces.push(ICuboid::from_ranges(
+                inner_x.clone(),
+                inner_y.clone(),
+                outer_z,
+            ));
+        }
+        pieces
+    }
+}
+
+// a union of cuboids, kept as disjoint pieces so volume is a simple sum. Useful for switching
+// regions on and off, like the reactor in 2021 day 22
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CuboidSet {
+    cuboids: Vec<ICuboid>,
+}
+
+impl CuboidSet {
+    pub fn new() -> Self {
+        CuboidSet { cuboids: vec![] }
+    }
+
+    pub fn cuboids(&self) -> &[ICuboid] {
+        &self.cuboids
+    }
+
+    pub fn insert(&mut self, cuboid: ICuboid) {
+        // clear out the space first so the new cuboid doesn't overlap anything
+        self.remove(&cuboid);
+        self.cuboids.push(cuboid);
+    }
+
+    pub fn remove(&mut self, cuboid: &ICuboid) {
+        self.cuboids = self
+            .cuboids
+            .iter()
+            .flat_map(|existing| existing.difference(cuboid))
+            .collect();
+    }
+
+    pub fn contains(&self, pt: &IPoint3D) -> bool {
+        self.cuboids.iter().any(|cuboid| cuboid.contains(pt))
+    }
+
+    pub fn volume(&self) -> i64 {
+        self.cuboids.iter().map(ICuboid::volume).sum()
+    }
+
+    // the part of the set inside bounds
+    pub fn intersect(&self, bounds: &ICuboid) -> CuboidSet {
+        CuboidSet {
+            cuboids: self
+                .cuboids
+                .iter()
+                .filter_map(|cuboid| cuboid.intersect(bounds))
+                .collect(),
+        }
+    }
+}
+
+impl FromIterator<ICuboid> for CuboidSet {
+    fn from_iter<I: IntoIterator<Item = ICuboid>>(iter: I) -> Self {
+        let mut set = CuboidSet::new();
+        for cuboid in iter {
+            set.insert(cuboid);
+        }
+        set
+    }
+}
+
+// a 3D grid that only stores the points that have something in them
+#[derive(Clone, Debug, Default)]
+pub struct SparseGrid3D<T> {
+    data: HashMap<IPoint3D, T>,
+}
+
+impl<T> FromIterator<(IPoint3D, T)> for SparseGrid3D<T> {
+    fn from_iter<TIter: IntoIterator<Item = (IPoint3D, T)>>(iter: TIter) -> Self {
+        SparseGrid3D {
+            data: iter.into_iter().collect(),
+        }
+    }
+}
+
+// a set of points, like the cubes of a lava droplet
+impl FromIterator<IPoint3D> for SparseGrid3D<()> {
+    fn from_iter<TIter: IntoIterator<Item = IPoint3D>>(iter: TIter) -> Self {
+        iter.into_iter().map(|pt| (pt, ())).collect()
+    }
+}
+
+impl<T> SparseGrid3D<T> {
+    pub fn new() -> Self {
+        SparseGrid3D {
+            data: HashMap::new(),
+        }
+    }
+
+    pub fn points(&self) -> impl Iterator<Item = &IPoint3D> {
+        self.data.keys()
+    }
+
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    // the smallest cuboid containing every point
+    pub fn bounds(&self) -> Option<ICuboid> {
+        let first = *self.points().next()?;
+        Some(
+            self.points()
+                .fold(ICuboid::new(first, first), |bounds, pt| {
+                    ICuboid::new(
+                        ipt3(
+                            min(bounds.origin.x, pt.x),
+                            min(bounds.origin.y, pt.y),
+                            min(bounds.origin.z, pt.z),
+                        ),
+                        ipt3(
+                            max(bounds.terminex.x, pt.x),
+                            max(bounds.terminex.y, pt.y),
+                            max(bounds.terminex.z, pt.z),
+                        ),
+                    )
+                }),
+        )
+    }
+
+    pub fn contains(&self, pt: &IPoint3D) -> bool {
+        self.data.contains_key(pt)
+    }
+
+    pub fn get(&self, pt: &IPoint3D) -> Option<&T> {
+        self.data.get(pt)
+    }
+
+    pub fn set(&mut self, pt: IPoint3D, value: T) -> Option<T> {
+        self.data.insert(pt, value)
+    }
+
+    pub fn remove(&mut self, pt: &IPoint3D) -> Option<T> {
+        self.data.remove(pt)
+    }
+
+    // the number of faces of filled points that aren't touching another filled point
+    pub fn surface_area(&self) -> usize {
+        self.points()
+            .flat_map(|pt| pt.face_neighbors())
+            .filter(|neighbor| !self.contains(neighbor))
+            .count()
+    }
+
+    // the empty points connected to the outside, within a layer one point thick around bounds
+    pub fn exterior(&self) -> HashSet<IPoint3D> {
+        let Some(bounds) = self.bounds() else {
+            return HashSet::new();
+        };
+        let bounds = ICuboid::new(
+            bounds.origin - ipt3(1, 1, 1),
+            bounds.terminex + ipt3(1, 1, 1),
+        );
+        flood_fill_3d(bounds.origin, |pt| {
+            bounds.contains(pt) && !self.contains(pt)
+        })
+    }
+
+    // like surface_area, but ignoring faces that only touch pockets of air trapped inside
+    pub fn exterior_surface_area(&self) -> usize {
+        let exterior = self.exterior();
+        self.points()
+            .flat_map(|pt| pt.face_neighbors())
+            .filter(|neighbor| exterior.contains(neighbor))
+            .count()
+    }
+}
+
+impl<T> IntoIterator for SparseGrid3D<T> {
+    type Item = (IPoint3D, T);
+    type IntoIter = std::collections::hash_map::IntoIter<IPoint3D, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.data.into_iter()
+    }
+}
+
+impl<T> Index<&IPoint3D> for SparseGrid3D<T> {
+    type Output = T;
+
+    fn index(&self, point: &IPoint3D) -> &T {
+        &self.data[point]
+    }
+}
+
+// every point reachable from start through faces, as long as passable allows it. passable has to
+// reject points eventually, or this will never finish
+pub fn flood_fill_3d<F>(start: IPoint3D, passable: F) -> HashSet<IPoint3D>
+where
+    F: Fn(&IPoint3D) -> bool,
+{
+    let mut filled = HashSet::new();
+    if !passable(&start) {
+        return filled;
+    }
+    let mut queue = VecDeque::from([start]);
+    filled.insert(start);
+    while let Some(pt) = queue.pop_front() {
+        for neighbor in pt.face_neighbors() {
+            if passable(&neighbor) && filled.insert(neighbor) {
+                queue.push_back(neighbor);
+            }
+        }
+    }
+    filled
+}
+
+// bricks after they've fallen as far as they can, and which bricks are resting on which. Indexes
+// match the bricks passed to settle_bricks
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SupportGraph {
+    pub bricks: Vec<Cuboid>,
+    // brick at key is supported by brick at values
+    pub supported_by: Vec<Vec<usize>>,
+    // brick at key supports bricks at values
+    pub supports: Vec<Vec<usize>>,
+}
+
+// drops every brick straight down until it lands on another brick or its bottom reaches floor.
+// Bricks that already start below floor are an error
+pub fn settle_bricks(bricks: &[Cuboid], floor: usize) -> Result<SupportGraph> {
+    if let Some(brick) = bricks.iter().find(|brick| brick.min_z() < floor) {
+        bail!("{brick:?} starts below the floor at z={floor}");
+    }
+    let mut graph = SupportGraph {
+        bricks: bricks.to_vec(),
+        supported_by: vec![vec![]; bricks.len()],
+        supports: vec![vec![]; bricks.len()],
+    };
+
+    // bricks fall in order from the bottom up, so everything below has already landed. The height
+    // map keeps the top z of each column and which brick is there
+    let mut order: Vec<usize> = (0..bricks.len()).collect();
+    order.sort_by_key(|ix| bricks[*ix].min_z());
+    let mut heights: HashMap<(usize, usize), (usize, usize)> = HashMap::new();
+    for ix in order {
+        let brick = bricks[ix];
+        let columns: Vec<_> = brick.x_range().cartesian_product(brick.y_range()).collect();
+        let top_below = columns
+            .iter()
+            .filter_map(|column| heights.get(column))
+            .map(|(z, _)| *z)
+            .max();
+        let rest_on = match top_below {
+            Some(z) if z + 1 >= floor => z + 1,
+            _ => floor,
+        };
+
+        let mut supported_by: Vec<usize> = columns
+            .iter()
+            .filter_map(|column| heights.get(column))
+            .filter(|(z, _)| z + 1 == rest_on)
+            .map(|(_, below)| *below)
+            .collect();
+        supported_by.sort_unstable();
+        supported_by.dedup();
+        for below in &supported_by {
+            graph.supports[*below].push(ix);
+        }
+        graph.supported_by[ix] = supported_by;
+
+        let settled = brick.shift_down_by(brick.min_z() - rest_on);
+        let top = settled.z_range().end - 1;
+        for column in columns {
+            heights.insert(column, (top, ix));
+        }
+        graph.bricks[ix] = settled;
+    }
+
+    Ok(graph)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+    use crate::WrappedOption;
+
+    #[test]
+    fn rotations() {
+        let all = Rotation3D::all();
+        assert_eq!(24, all.len());
+        assert_eq!(24, all.iter().collect::<HashSet<_>>().len());
+        assert!(all.contains(&Rotation3D::IDENTITY));
+
+        let pt = ipt3(1, 2, 3);
+        let images: HashSet<_> = all.iter().map(|r| r.apply(pt)).collect();
+        assert_eq!(24, images.len());
+
+        for a in &all {
+            assert_eq!(Rotation3D::IDENTITY, a.then(&a.inverse()));
+            for b in &all {
+                let composed = a.then(b);
+                assert!(all.contains(&composed));
+                assert_eq!(b.apply(a.apply(pt)), composed * pt);
+            }
+        }
+    }
+
+    #[test]
+    fn aligns_points() -> Result<()> {
+        let reference: Vec<IPoint3D> = ["1,2,3", "-4,5,6", "7,-8,9", "10,11,-12", "0,0,0"]
+            .iter()
+            .map(|pt| pt.parse())
+            .collect::<Result<_>>()?;
+        let expected = Alignment3D {
+            rotation: Rotation3D::all()[17],
+            translation: ipt3(100, -20, 3),
+        };
+        // what another observer would see, plus a point the reference can't see
+        let inverse = expected.rotation.inverse();
+        let mut observed: Vec<IPoint3D> = reference
+            .iter()
+            .map(|pt| inverse.apply(*pt - expected.translation))
+            .collect();
+        observed.push(ipt3(50, 50, 50));
+
+        let alignment = align_points(&reference, &observed, 4).ok_or_invalid()?;
+        assert_eq!(expected, alignment);
+        assert_eq!(reference[2], alignment.apply(observed[2]));
+
+        assert_eq!(None, align_points(&reference, &observed[..3], 4));
+
+        Ok(())
+    }
+
+    #[test]
+    fn point_arithmetic() {
+        let a = ipt3(1, -2, 3);
+        let b = ipt3(-4, 5, 6);
+        assert_eq!(ipt3(-3, 3, 9), a + b);
+        assert_eq!(ipt3(5, -7, -3), a - b);
+        assert_eq!(ipt3(-2, 4, -6), -a * 2);
+        assert_eq!(15, a.manhattan_distance(b));
+        assert!(a
+            .face_neighbors()
+            .iter()
+            .all(|n| n.manhattan_distance(a) == 1));
+    }
+
+    #[test]
+    fn cuboid_operations() {
+        let a = ICuboid::new(ipt3(0, 0, 0), ipt3(2, 2, 2));
+        let b = ICuboid::new(ipt3(3, 3, 3), ipt3(1, 1, 1));
+        assert_eq!(27, a.volume());
+        assert_eq!(ipt3(1, 1, 1), b.origin);
+        assert!(a.overlaps(&b));
+        assert!(!a.overlaps(&a.offset(ipt3(0, -3, 0))));
+        assert_eq!(
+            Some(ICuboid::new(ipt3(1, 1, 1), ipt3(2, 2, 2))),
+            a.intersect(&b)
+        );
+
+        let pieces = a.difference(&b);
+        assert_eq!(19, pieces.iter().map(ICuboid::volume).sum::<i64>());
+        assert!(pieces.iter().all(|piece| !piece.overlaps(&b)));
+        for (i, p) in pieces.iter().enumerate() {
+            assert!(pieces[i + 1..].iter().all(|q| !p.overlaps(q)));
+        }
+
+        // a hole in the middle takes all six pieces
+        let hole = ICuboid::new(ipt3(1, 1, 1), ipt3(1, 1, 1));
+        assert_eq!(6, a.difference(&hole).len());
+        assert!(a.difference(&a).is_empty());
+    }
+
+    #[test]
+    fn cuboid_sets() {
+        // the small sample from 2021 day 22
+        let mut set = CuboidSet::new();
+        set.insert(ICuboid::new(ipt3(10, 10, 10), ipt3(12, 12, 12)));
+        set.insert(ICuboid::new(ipt3(11, 11, 11), ipt3(13, 13, 13)));
+        assert_eq!(46, set.volume());
+        set.remove(&ICuboid::new(ipt3(9, 9, 9), ipt3(11, 11, 11)));
+        assert_eq!(38, set.volume());
+        set.insert(ICuboid::new(ipt3(10, 10, 10), ipt3(10, 10, 10)));
+        assert_eq!(39, set.volume());
+
+        assert!(set.contains(&ipt3(10, 10, 10)));
+        assert!(!set.contains(&ipt3(11, 11, 11)));
+        let corner = ICuboid::new(ipt3(12, 12, 12), ipt3(20, 20, 20));
+        assert_eq!(8, set.intersect(&corner).volume());
+    }
+
+    #[test]
+    fn surface_area() {
+        // the lava droplet from 2022 day 18, which has one pocket of air inside
+        let droplet: SparseGrid3D<()> = [
+            (2, 2, 2),
+            (1, 2, 2),
+            (3, 2, 2),
+            (2, 1, 2),
+            (2, 3, 2),
+            (2, 2, 1),
+            (2, 2, 3),
+            (2, 2, 4),
+            (2, 2, 6),
+            (1, 2, 5),
+            (3, 2, 5),
+            (2, 1, 5),
+            (2, 3, 5),
+        ]
+        .into_iter()
+        .map(|(x, y, z)| ipt3(x, y, z))
+        .collect();
+
+        assert_eq!(64, droplet.surface_area());
+        assert_eq!(58, droplet.exterior_surface_area());
+        assert!(!droplet.exterior().contains(&ipt3(2, 2, 5)));
+        assert_eq!(
+            Some(ICuboid::new(ipt3(1, 1, 1), ipt3(3, 3, 6))),
+            droplet.bounds()
+        );
+
+        assert_eq!(26, ipt3(0, 0, 0).all_neighbors().count());
+        let inside = flood_fill_3d(ipt3(0, 0, 0), |pt| {
+            ICuboid::new(ipt3(0, 0, 0), ipt3(2, 2, 2)).contains(pt)
+        });
+        assert_eq!(27, inside.len());
+    }
+
+    #[test]
+    fn settles_bricks() -> Result<()> {
+        let brick = |a: (usize, usize, usize), b: (usize, usize, usize)| {
+            Cuboid::from((
+                Point3D {
+                    x: a.0,
+                    y: a.1,
+                    z: a.2,
+                },
+                Point3D {
+                    x: b.0,
+                    y: b.1,
+                    z: b.2,
+                },
+            ))
+        };
+        // a bridge that lands on two pillars, and a brick off to the side that reaches the floor
+        let bricks = [
+            brick((1, 3, 9), (1, 5, 9)),
+            brick((0, 1, 3), (0, 1, 4)),
+            brick((0, 1, 12), (2, 1, 12)),
+            brick((2, 1, 5), (2, 1, 6)),
+        ];
+        let graph = settle_bricks(&bricks, 1)?;
+
+        assert_eq!(bricks[1].shift_down_by(2), graph.bricks[1]);
+        assert_eq!(bricks[3].shift_down_by(4), graph.bricks[3]);
+        assert_eq!(bricks[2].shift_down_by(9), graph.bricks[2]);
+        assert_eq!(vec![vec![], vec![], vec![1, 3], vec![]], graph.supported_by);
+        assert_eq!(vec![vec![], vec![2], vec![], vec![2]], graph.supports);
+        assert_eq!(1, graph.bricks[0].min_z());
+
+        // the pillar starting at z=3 is already below a floor at 4
+        assert!(settle_bricks(&bricks, 4).is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,274 @@
+use std::ops::Add;
+
+use crate::Objective;
+
+// Held–Karp bitmask DP for travelling salesman style problems. Runs in O(2^n * n^2) time and
+// O(2^n * n) memory, so it's good up to ~20 nodes where brute force permutations give up around 10
+
+// at 20 nodes the tables already take a few hundred MB, and every node past that more than doubles
+// them, so bigger graphs get no tour rather than running out of memory
+pub const MAX_TOUR_NODES: usize = 20;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum TourShape {
+    // visit every node once, ending anywhere
+    Path,
+    // visit every node once, then return to the start
+    Cycle,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Tour<T> {
+    pub length: T,
+    // nodes in visiting order. For cycles, the edge back to the first node is implied
+    pub nodes: Vec<usize>,
+}
+
+// distance(a, b) returns None when there is no edge from a to b. start only matters for paths,
+// cycles without a start are anchored at node 0 since every rotation has the same length. Returns
+// None for more than MAX_TOUR_NODES nodes or a start that isn't one of the nodes
+pub fn hamiltonian_tour<T, F>(
+    node_count: usize,
+    distance: F,
+    shape: TourShape,
+    start: Option<usize>,
+    objective: Objective,
+) -> Option<Tour<T>>
+where
+    T: Copy + Default + Ord + Add<Output = T>,
+    F: Fn(usize, usize) -> Option<T>,
+{
+    let n = node_count;
+    if n == 0 || n > MAX_TOUR_NODES || start.is_some_and(|start| start >= n) {
+        return None;
+    }
+
+    let starts: Vec<usize> = match (shape, start) {
+        (_, Some(start)) => vec![start],
+        (TourShape::Cycle, None) => vec![0],
+        (TourShape::Path, None) => (0..n).collect(),
+    };
+
+    // best[mask * n + last] = best length of a walk visiting exactly mask, ending at last
+    let full = (1_usize << n) - 1;
+    let mut best: Vec<Option<T>> = vec![None; n << n];
+    let mut parent: Vec<usize> = vec![usize::MAX; n << n];
+    for &s in &starts {
+        best[(1 << s) * n + s] = Some(T::default());
+    }
+
+    for mask in 1..=full {
+        for last in (0..n).filter(|last| mask & (1 << last) != 0) {
+            let Some(length) = best[mask * n + last] else {
+                continue;
+            };
+            for next in (0..n).filter(|next| mask & (1 << next) == 0) {
+                let Some(step) = distance(last, next) else {
+                    continue;
+                };
+                let candidate = length + step;
+                let ix = (mask | (1 << next)) * n + next;
+                if best[ix].is_none_or(|prev| objective.is_better(&candidate, &prev)) {
+                    best[ix] = Some(candidate);
+                    parent[ix] = last;
+                }
+            }
+        }
+    }
+
+    let mut result: Option<(T, usize)> = None;
+    for last in 0..n {
+        let Some(length) = best[full * n + last] else {
+            continue;
+        };
+        let length = match shape {
+            TourShape::Path => length,
+            TourShape::Cycle => match distance(last, starts[0]) {
+                Some(closing) => length + closing,
+                None => continue,
+            },
+        };
+        if result.is_none_or(|(prev, _)| objective.is_better(&length, &prev)) {
+            result = Some((length, last));
+        }
+    }
+
+    let (length, mut last) = result?;
+    let mut nodes = Vec::with_capacity(n);
+    let mut mask = full;
+    loop {
+        nodes.push(last);
+        let prev = parent[mask * n + last];
+        if prev == usize::MAX {
+            break;
+        }
+        mask ^= 1 << last;
+        last = prev;
+    }
+    nodes.reverse();
+
+    Some(Tour { length, nodes })
+}
+
+fn matrix_tour<T>(
+    distances: &[Vec<T>],
+    shape: TourShape,
+    start: Option<usize>,
+    objective: Objective,
+) -> Option<Tour<T>>
+where
+    T: Copy + Default + Ord + Add<Output = T>,
+{
+    hamiltonian_tour(
+        distances.len(),
+        |a, b| Some(distances[a][b]),
+        shape,
+        start,
+        objective,
+    )
+}
+
+pub fn shortest_hamiltonian_path<T>(distances: &[Vec<T>], start: Option<usize>) -> Option<Tour<T>>
+where
+    T: Copy + Default + Ord + Add<Output = T>,
+{
+    matrix_tour(distances, TourShape::Path, start, Objective::Minimize)
+}
+
+pub fn longest_hamiltonian_path<T>(distances: &[Vec<T>], start: Option<usize>) -> Option<Tour<T>>
+where
+    T: Copy + Default + Ord + Add<Output = T>,
+{
+    matrix_tour(distances, TourShape::Path, start, Objective::Maximize)
+}
+
+pub fn shortest_hamiltonian_cycle<T>(distances: &[Vec<T>]) -> Option<Tour<T>>
+where
+    T: Copy + Default + Ord + Add<Output = T>,
+{
+    matrix_tour(distances, TourShape::Cycle, None, Objective::Minimize)
+}
+
+pub fn longest_hamiltonian_cycle<T>(distances: &[Vec<T>]) -> Option<Tour<T>>
+where
+    T: Copy + Default + Ord + Add<Output = T>,
+{
+    matrix_tour(distances, TourShape::Cycle, None, Objective::Maximize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use itertools::Itertools;
+
+    fn sample_distances() -> Vec<Vec<u32>> {
+        vec![
+            vec![0, 12, 10, 19, 8],
+            vec![12, 0, 3, 7, 2],
+            vec![10, 3, 0, 6, 20],
+            vec![19, 7, 6, 0, 4],
+            vec![8, 2, 20, 4, 0],
+        ]
+    }
+
+    fn walk_length(distances: &[Vec<u32>], nodes: &[usize], closed: bool) -> u32 {
+        let open: u32 = nodes.windows(2).map(|w| distances[w[0]][w[1]]).sum();
+        if closed {
+            open + distances[nodes[nodes.len() - 1]][nodes[0]]
+        } else {
+            open
+        }
+    }
+
+    fn brute_force(distances: &[Vec<u32>], closed: bool, start: Option<usize>) -> (u32, u32) {
+        let lengths: Vec<u32> = (0..distances.len())
+            .permutations(distances.len())
+            .filter(|p| start.is_none_or(|s| p[0] == s))
+            .map(|p| walk_length(distances, &p, closed))
+            .collect();
+        (
+            *lengths.iter().min().unwrap(),
+            *lengths.iter().max().unwrap(),
+        )
+    }
+
+    #[test]
+    fn paths_match_brute_force() {
+        let distances = sample_distances();
+        for start in [None, Some(0), Some(3)] {
+            let (min, max) = brute_force(&distances, false, start);
+
+            let shortest = shortest_hamiltonian_path(&distances, start).unwrap();
+            assert_eq!(min, shortest.length);
+            assert_eq!(min, walk_length(&distances, &shortest.nodes, false));
+
+            let longest = longest_hamiltonian_path(&distances, start).unwrap();
+            assert_eq!(max, longest.length);
+            assert_eq!(max, walk_length(&distances, &longest.nodes, false));
+
+            if let Some(start) = start {
+                assert_eq!(start, shortest.nodes[0]);
+                assert_eq!(start, longest.nodes[0]);
+            }
+        }
+    }
+
+    #[test]
+    fn cycles_match_brute_force() {
+        let distances = sample_distances();
+        let (min, max) = brute_force(&distances, true, None);
+
+        let shortest = shortest_hamiltonian_cycle(&distances).unwrap();
+        assert_eq!(min, shortest.length);
+        assert_eq!(5, shortest.nodes.iter().unique().count());
+        assert_eq!(min, walk_length(&distances, &shortest.nodes, true));
+
+        let longest = longest_hamiltonian_cycle(&distances).unwrap();
+        assert_eq!(max, longest.length);
+        assert_eq!(max, walk_length(&distances, &longest.nodes, true));
+    }
+
+    #[test]
+    fn missing_edges_are_skipped() {
+        // 0 - 1 - 2 is the only hamiltonian path, and there is no cycle
+        let edges = [(0, 1, 5), (1, 2, 7)];
+        let distance = |a: usize, b: usize| {
+            edges
+                .iter()
+                .find(|&&(x, y, _)| (x, y) == (a, b) || (y, x) == (a, b))
+                .map(|&(_, _, d)| d)
+        };
+
+        let path = hamiltonian_tour(3, distance, TourShape::Path, None, Objective::Minimize);
+        assert_eq!(12, path.as_ref().unwrap().length);
+        assert_eq!(1, path.unwrap().nodes[1]);
+
+        assert_eq!(
+            None,
+            hamiltonian_tour(3, distance, TourShape::Cycle, None, Objective::Maximize)
+        );
+    }
+
+    #[test]
+    fn rejects_bad_inputs() {
+        let distance = |_, _| Some(1);
+        assert_eq!(
+            None,
+            hamiltonian_tour(3, distance, TourShape::Path, Some(3), Objective::Minimize)
+        );
+        assert_eq!(
+            None,
+            hamiltonian_tour(
+                MAX_TOUR_NODES + 1,
+                distance,
+                TourShape::Cycle,
+                None,
+                Objective::Minimize
+            )
+        );
+        assert_eq!(
+            None,
+            hamiltonian_tour(0, distance, TourShape::Path, None, Objective::Minimize)
+        );
+    }
+}